@@ -1,51 +1,111 @@
 #[macro_use]
 pub mod util;
 
-use colored::Colorize;
-
-macro_rules! run_problem {
-    ( $run_all: expr, $pb: ident ) => {{
-        use std::time::Instant;
-
-        let start = Instant::now();
-        let soluce = $pb::solve();
-        let elapsed = start.elapsed();
-
-        println!("{}: {:<20} ({:.0?})", stringify!($pb), soluce, elapsed);
-    }};
-    ( $run_all: expr, #[skip] $pb: ident ) => {
-        if $run_all {
-            run_problem!($run_all, $pb);
-        } else {
-            println!("{}", format!("{}: skipped", stringify!($pb)).dimmed());
+mod runner;
+
+macro_rules! some_or_none {
+    () => {
+        None
+    };
+    ( $x: expr ) => {
+        Some($x)
+    };
+}
+
+macro_rules! problem {
+    ( $pb: ident $( = $hash: literal )? ) => {
+        runner::Problem {
+            name: stringify!($pb),
+            skip: false,
+            answer_hash: some_or_none!($( $hash )?),
+            solve: || $pb::solve().to_string(),
+        }
+    };
+    ( #[skip] $pb: ident $( = $hash: literal )? ) => {
+        runner::Problem {
+            skip: true,
+            ..problem!($pb $( = $hash )?)
+        }
+    };
+}
+
+// Problems without a registered answer don't get a test.
+#[cfg(test)]
+macro_rules! answer_test {
+    ( $( #[skip] )? $pb: ident ) => {};
+    ( $pb: ident = $hash: literal ) => {
+        #[test]
+        fn $pb() {
+            let answer = super::$pb::solve().to_string();
+            assert_eq!(crate::runner::hash_answer(&answer), $hash);
+        }
+    };
+    ( #[skip] $pb: ident = $hash: literal ) => {
+        #[test]
+        #[ignore]
+        fn $pb() {
+            let answer = super::$pb::solve().to_string();
+            assert_eq!(crate::runner::hash_answer(&answer), $hash);
         }
     };
 }
 
 macro_rules! main {
-    ( $( $( #[ $mode: ident ] )? $pb: ident ),* ) => {
+    ( $( $( #[ $mode: ident ] )? $pb: ident $( = $hash: literal )? ),* $(,)? ) => {
         $( mod $pb; )*
 
         fn main() {
-            let args: Vec<_> = std::env::args().skip(1).collect();
-
-            let run_all = match args.as_slice() {
-                [] => false,
-                [arg] if ["-a", "--all"].contains(&arg.as_str()) => true,
-                _ => panic!("the only known parameters are --all"),
-            };
+            runner::main(&[ $( problem!( $( #[ $mode ] )? $pb $( = $hash )? ) ),* ]);
+        }
 
-            $( run_problem!( run_all, $( #[ $mode ] )? $pb ); )*
+        #[cfg(test)]
+        mod answers {
+            $( answer_test!( $( #[ $mode ] )? $pb $( = $hash )? ); )*
         }
     };
 }
 
+// Answers are registered as the SHA-256 of their printed value, to avoid
+// spoiling them.
 main! {
-    problem_001, problem_002, problem_003, problem_004, problem_005, problem_006, problem_007,
-    problem_008, problem_009, problem_010, problem_011, #[skip] problem_012, problem_013, #[skip]
-    problem_014, problem_015, problem_017, problem_018, problem_020, problem_021, problem_022,
-    #[skip] problem_023, problem_024, problem_025, #[skip] problem_027, problem_029, problem_030,
-    problem_031, problem_034, #[skip] problem_035, problem_036, problem_039, problem_040,
-    problem_041, problem_067, problem_101, problem_102, #[skip] problem_104, #[skip] problem_108,
-    #[skip] problem_201, problem_700
+    problem_001 = "c0b20f4665d0388d564f0b6ecf3edc9f9480cb15fff87198b95701d9f5fe1f7b",
+    problem_002 = "1f5882e19314ac13acca52ad5503184b3cb1fd8dbeea82e0979d799af2361704",
+    problem_003 = "5c09f0554518a413e58e6bc5964ba90655713483d0b2bbc94572ad6b0b4dda28",
+    problem_004 = "aa74f52b4c428d89606b411bc165eb81a6266821ecc9b4f30cdb70c5c930f4d9",
+    problem_005 = "1ba90ab11bfb2d2400545337212b0de2a5c7f399215175ade6396e91388912b1",
+    problem_006 = "537942be3eb323c507623a6a73fa87bf5aeb97b7c7422993a82aa7c15f6d9cd6",
+    problem_007 = "ecbe74e25cfa4763dbc304ccac2ffb9912e9625cd9993a84bd0dd6d7dc0ca021",
+    problem_008 = "b9fb30b6553415e9150051ce5710a93d0f55b22557c0068d8e16619a388f145a",
+    problem_009 = "d912d9d473ef86f12da1fb2011c5c0c155bd3a0ebdb4bbd7ea275cecdcb63731",
+    problem_010 = "bed2d160e02f0540f19a64ca738aacb79cfcd08ba7e2421567b16cb6e7e3e90e",
+    problem_011 = "9ded5bc849d33e477aa9c944138d34f0aacc485a372e84464e8a572712a5b7da",
+    problem_012 = "3e7be445b6c19e6db58c2482005c1f78cb74011a4279249ca632011a9f1b61a2",
+    problem_013 = "3cb265a96c5645a9ad11d47551f015c25f3f99792c951617656d84626fbc4868",
+    #[skip] problem_014 = "78a262dd40eba0f7195686ec7f3891a39437523456f8d16fa9065a34409eeac6",
+    problem_015 = "7b8f812ca89e311e1b16b903de76fa7b0800a939b3028d9dc4d35f6fa4050281",
+    problem_017 = "1a455b216c6e916943acf3fa4c7e57a7a5cac66d97cc51befca810c223ef9c23",
+    problem_018 = "fde3f2e7127f6810eb4160bf7bb0563240d78c9d75a9a590b6d6244748a7f4ff",
+    problem_020 = "c86a2932e1c79343a3c16fb218b9944791aaeedd3e30c87d1c7f505c0e588f7c",
+    problem_021 = "e8c6ef4a1736a245b5682e0262c5c43862cfb233ca5e286be2f5bb4d8a974ecf",
+    problem_022 = "85148c096c25e3ed3da55c7e9c89448018b0f5f53ad8d042129c33d9beac6736",
+    problem_023 = "42e2552a2f589e021824339e2508629ffa00b3489ea467f47e77a1ea97e735c9",
+    problem_024 = "4677b3d9daa3b30a9665e4558f826e04f7833dda886b8ef24f7176519a0db537",
+    problem_025 = "7d398da8791745001b3d1c41030676d1c036687eb1ab32e0b5a1832e7579c073",
+    problem_027 = "e4110e0852a2f70703f0081fc91c4a20f595919a038729cb37c564d68b875c6f",
+    problem_029 = "a207c35d8417aeed4c9e78bcf83f936cd8191c702893be62aa690ce16bc909ca",
+    problem_030 = "46e68e4199ab0a663ab306651528b06756556c9f0d8b819095af45e036dfbe6b",
+    problem_031 = "8de34b4ba97b184c7a2096b9266776175242b87d67bc8d77d7289be6f70cd105",
+    problem_034 = "728b8d7d6d5d34cad9cbb7c3ea15f807ae57144594b1740b3c73b82314ccd1ed",
+    #[skip] problem_035 = "02d20bbd7e394ad5999a4cebabac9619732c343a4cac99470c03e23ba2bdc2bc",
+    problem_036 = "9480c0160719234b57defc0681c0949a175ffb3ff4a3bf5e8163ac843f383f35",
+    problem_039 = "fd0f7e53c5b02b688a57ee37f3d52065cb168a7b9fd5a3abd93d37e1559fbd30",
+    problem_040 = "d29d53701d3c859e29e1b90028eec1ca8e2f29439198b6e036c60951fb458aa1",
+    problem_041,
+    problem_067 = "53f66b6783cb7552d83015df01b0d5229569fce1dd7d1856335c7244b9a3ded6",
+    problem_101 = "e8539f8b271851cad65d551354874d3086fa9ff7b6f6a2ab9890d63f5ba16c68",
+    problem_102 = "9d693eeee1d1899cbc50b6d45df953d3835acf28ee869879b45565fccc814765",
+    problem_104 = "87dfcf5471e77980d098ff445701dbada0f6f7bac2fa5e43fa7685ec435040e1",
+    problem_108 = "3dea386e2c4a8a0633b667fdd4beacd8bb3fe27c282f886c828ad7d6b42c2d73",
+    problem_201 = "b4e86186652a11df0b9ec8f601c68b4823ae0bafd96357051371fde5d11a25ed",
+    problem_700 = "3dccee8e873d2c9c2f8359417e666b702f97b60b90b229e3c41190909ff9388b",
 }
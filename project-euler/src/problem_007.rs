@@ -1,7 +1,7 @@
-use crate::util::primes::primes;
+use crate::util::primes::nth_prime;
 
 const INDEX: usize = 10_001;
 
 pub fn solve() -> u64 {
-    primes().nth(INDEX - 1).unwrap()
+    nth_prime(INDEX)
 }
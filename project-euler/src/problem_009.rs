@@ -1,15 +1,11 @@
+use crate::util::sequences::pythagorean_triples;
+
 const TARGET: u64 = 1_000;
 
 pub fn solve() -> u64 {
-    for a in 1..TARGET {
-        for b in 1..(TARGET - a) {
-            let c = TARGET - a - b;
-
-            if a.pow(2) + b.pow(2) == c.pow(2) {
-                return a * b * c;
-            }
-        }
-    }
-
-    unreachable!()
+    pythagorean_triples(TARGET)
+        .with_multiples()
+        .find(|triple| triple.perimeter() == TARGET)
+        .map(|triple| triple.a * triple.b * triple.c)
+        .unwrap()
 }
@@ -46,7 +46,7 @@ fn all_lines() -> impl Iterator<Item = [(usize, usize); 4]> {
 pub fn solve() -> u64 {
     all_lines()
         .map(|line| {
-            std::array::IntoIter::new(line)
+            IntoIterator::into_iter(line)
                 .map(|(x, y)| INPUT[x][y])
                 .product()
         })
@@ -1,6 +1,6 @@
-use crate::util::arithmetic::Divisors;
+use crate::util::multiplicative::nb_divisors;
 
-const MIN_COUNT: usize = 500;
+const MIN_COUNT: u64 = 500;
 
 fn triangles() -> impl Iterator<Item = u64> {
     (1..).scan(0, |sum, x| {
@@ -10,7 +10,5 @@ fn triangles() -> impl Iterator<Item = u64> {
 }
 
 pub fn solve() -> u64 {
-    triangles()
-        .find(|x| x.divisors().count() >= MIN_COUNT)
-        .unwrap()
+    triangles().find(|x| nb_divisors(*x) >= MIN_COUNT).unwrap()
 }
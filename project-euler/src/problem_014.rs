@@ -4,7 +4,7 @@ use std::collections::HashMap;
 const MAX: u64 = 1_000_000;
 
 fn collatz_iter(n: u64) -> u64 {
-    if n % 2 == 0 {
+    if n.is_multiple_of(2) {
         n / 2
     } else {
         3 * n + 1
@@ -1,7 +1,7 @@
-use crate::util::arithmetic::Divisors;
+use crate::util::multiplicative::sigma;
 
 fn is_amicable(x: u64) -> bool {
-    let d = |n: u64| n.divisors().filter(|d| *d < n).sum();
+    let d = |n: u64| sigma(n, 1) - n;
     x > 1 && d(x) != x && d(d(x)) == x
 }
 
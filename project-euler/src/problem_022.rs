@@ -1,14 +1,12 @@
+use crate::util::data::quoted_words;
+use crate::util::text::word_value;
+
 pub fn solve() -> u64 {
-    let mut names: Vec<_> = include_str!("data/problem_022.txt")
-        .split(',')
-        .map(|name| name.trim().trim_matches('"'))
-        .collect();
+    let mut names = quoted_words(include_str!("data/problem_022.txt")).unwrap();
     names.sort_unstable();
     names
         .iter()
         .enumerate()
-        .map(|(i, name)| {
-            (i as u64 + 1) * name.bytes().map(|b| 1 + u64::from(b - b'A')).sum::<u64>()
-        })
+        .map(|(i, name)| (i as u64 + 1) * word_value(name))
         .sum()
 }
@@ -1,26 +1,24 @@
-use crate::util::arithmetic::Divisors;
-use fxhash::FxHashSet;
+use crate::util::multiplicative::LinearSieve;
 
-const MAX: u64 = 28123;
+const MAX: usize = 28123;
 
-fn is_abundant(n: u64) -> bool {
-    n.divisors().filter(|k| *k < n).sum::<u64>() > n
+fn abundants(max: usize) -> Vec<usize> {
+    let sigma = LinearSieve::new(max as u32).sigma(1);
+
+    (1..=max).filter(|&n| sigma[n] > 2 * n as u64).collect()
 }
 
-pub fn solve() -> u64 {
-    let abundants: Vec<_> = (1..=MAX).filter(|n| is_abundant(*n)).collect();
+pub fn solve() -> usize {
+    let abundants = abundants(MAX);
+    let mut is_sum = vec![false; MAX + 1];
 
-    let all_sums: FxHashSet<_> = abundants
-        .iter()
-        .flat_map(|x| abundants.iter().map(move |y| x + y))
-        .filter(|n| *n <= MAX)
-        .collect();
+    for (i, x) in abundants.iter().enumerate() {
+        for y in abundants[i..].iter().take_while(|&&y| x + y <= MAX) {
+            is_sum[x + y] = true;
+        }
+    }
 
-    let mut all_sums: Vec<_> = all_sums.into_iter().collect();
-    all_sums.push(0);
-    all_sums.push(MAX + 1);
-    all_sums.sort_unstable();
-    all_sums.windows(2).flat_map(|win| win[0] + 1..win[1]).sum()
+    (1..=MAX).filter(|&n| !is_sum[n]).sum()
 }
 
 #[cfg(test)]
@@ -28,8 +26,7 @@ mod test {
     use super::*;
 
     #[test]
-    fn test_is_abundant() {
-        assert!(is_abundant(12));
-        assert!(!is_abundant(28));
+    fn test_abundants() {
+        assert_eq!(abundants(30), [12, 18, 20, 24, 30]);
     }
 }
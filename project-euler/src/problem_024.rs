@@ -1,5 +1,10 @@
-use crate::util::algorithms::Permutations;
+use crate::util::algorithms::nth_permutation;
 
 pub fn solve() -> u64 {
-    9876543210.permutations().nth(1_000_001).unwrap()
+    let digits: Vec<u64> = (0..=9).collect();
+
+    nth_permutation(&digits, 999_999)
+        .unwrap()
+        .into_iter()
+        .fold(0, |acc, d| 10 * acc + d)
 }
@@ -1,4 +1,4 @@
-use crate::util::primes::is_prime;
+use crate::util::primes::{cache_primes, is_prime};
 use std::convert::TryFrom;
 
 fn consecutive_primes(a: i64, b: i64) -> i64 {
@@ -12,6 +12,9 @@ fn consecutive_primes(a: i64, b: i64) -> i64 {
 }
 
 pub fn solve() -> i64 {
+    // Sequences of primes reach values up to about 80² + 999 × 80 + 1000
+    cache_primes(100_000);
+
     ((-999..=999).flat_map(|a| (-1000..=1000).map(move |b| (a, b))))
         .max_by_key(|(a, b)| consecutive_primes(*a, *b))
         .map(|(a, b)| a * b)
@@ -1,22 +1,7 @@
-use cached::proc_macro::cached;
-use std::cmp::min;
+use crate::util::dp::count_unbounded;
 
-const COINS: [u8; 8] = [1, 2, 5, 10, 20, 50, 100, 200];
-
-#[cached]
-fn count_combinations(target: u8, max: u8) -> u64 {
-    if target == 0 {
-        1
-    } else {
-        COINS
-            .iter()
-            .copied()
-            .take_while(|c| *c <= min(target, max))
-            .map(|c| count_combinations(target - c, c))
-            .sum()
-    }
-}
+const COINS: [usize; 8] = [1, 2, 5, 10, 20, 50, 100, 200];
 
 pub fn solve() -> u64 {
-    count_combinations(200, 200)
+    count_unbounded::<u64>(&COINS, 200)[200]
 }
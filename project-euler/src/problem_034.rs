@@ -3,7 +3,7 @@ use crate::util::arithmetic::Digits;
 pub fn solve() -> u64 {
     let factorial = |n| -> u64 { (1..=n).product() };
 
-    (10..100_000)
+    (10..100_000u64)
         .filter(|&n| n == n.digits(10).map(u64::from).map(factorial).sum())
         .sum()
 }
@@ -6,7 +6,7 @@ fn circle(n: u64) -> impl Iterator<Item = u64> {
     let nb_digits = n.nb_digits(10);
 
     (0..nb_digits).scan(n, move |n, _| {
-        *n = (*n % 10) * 10u64.pow(u32::from(nb_digits) - 1) + (*n / 10);
+        *n = (*n % 10) * 10u64.pow(nb_digits - 1) + (*n / 10);
         Some(*n)
     })
 }
@@ -20,7 +20,7 @@ pub fn solve() -> usize {
 
     (2..=1_000_000)
         .filter(|n| {
-            if candidates.contains(&n) {
+            if candidates.contains(n) {
                 let res = is_circular_prime(*n);
 
                 if !res {
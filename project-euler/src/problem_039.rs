@@ -1,17 +1,15 @@
-use rayon::prelude::*;
+use crate::util::sequences::{count_triples_by, pythagorean_triples, PythagoreanTriple};
+
+const MAX_PERIMETER: u64 = 999;
 
 pub fn solve() -> u64 {
-    (1..1000)
-        .collect::<Vec<_>>()
-        .into_par_iter()
-        .max_by_key(|p| {
-            (1..=*p)
-                .flat_map(|a| (1..(1000 - a)).map(move |b| (a, b)))
-                .filter(|(a, b)| {
-                    let c = p - a - b;
-                    a * a + b * b == c * c
-                })
-                .count()
-        })
+    let counts = count_triples_by(
+        pythagorean_triples(MAX_PERIMETER).with_multiples(),
+        MAX_PERIMETER,
+        PythagoreanTriple::perimeter,
+    );
+
+    (0..=MAX_PERIMETER)
+        .max_by_key(|&p| counts[p as usize])
         .unwrap()
 }
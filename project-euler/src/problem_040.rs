@@ -13,8 +13,7 @@ fn fractional_digit(mut n: u32) -> u8 {
     }
 
     let num = 10u32.pow(digits - 1) + (n - 1) / digits;
-    let digit = num.digits(10).nth(((n - 1) % digits) as usize).unwrap();
-    digit
+    num.digits(10).nth(((n - 1) % digits) as usize).unwrap()
 }
 
 pub fn solve() -> u64 {
@@ -37,11 +36,11 @@ mod test {
             loop {
                 x += 1;
 
-                if n <= usize::from(x.nb_digits(10)) {
+                if n <= x.nb_digits(10) as usize {
                     return x.digits(10).nth(n - 1).unwrap();
                 }
 
-                n -= usize::from(x.nb_digits(10));
+                n -= x.nb_digits(10) as usize;
             }
         }
 
@@ -1,33 +1,18 @@
-use crate::util::linalg::Vector2;
-
-fn origin_in_triangle([a, b, c]: [Vector2<i64>; 3]) -> bool {
-    // Rotate vector by 90°
-    let rot = |u: Vector2<i64>| v![*u.y(), -*u.x()];
+use std::convert::TryInto;
 
-    // Sign of the angle between u and v
-    let sign = |u: Vector2<i64>, v: Vector2<i64>| {
-        let angle = rot(u).dot(v);
-        angle / angle.abs()
-    };
+use crate::util::data::coordinates;
+use crate::util::geometry::{point_in_polygon, Location};
+use crate::util::linalg::Vector2;
 
-    let signs = [sign(b - a, -a), sign(c - b, -b), sign(a - c, -c)];
-    signs.iter().all(|s| *s >= 0) || signs.iter().all(|s| *s <= 0)
+fn origin_in_triangle(triangle: [Vector2<i64>; 3]) -> bool {
+    point_in_polygon(&v![0, 0], &triangle) != Location::Outside
 }
 
 pub fn solve() -> usize {
-    include_str!("data/problem_102.txt")
-        .lines()
-        .map(|raw| {
-            let coords: Vec<_> = raw
-                .split(',')
-                .map(|num| num.parse::<i64>().unwrap())
-                .collect();
-
-            match *coords.as_slice() {
-                [x1, y1, x2, y2, x3, y3] => [v![x1, y1], v![x2, y2], v![x3, y3]],
-                _ => unreachable!(),
-            }
-        })
+    coordinates(include_str!("data/problem_102.txt"))
+        .unwrap()
+        .into_iter()
+        .map(|points| points.try_into().expect("triangles have three vertices"))
         .filter(|triangle| origin_in_triangle(*triangle))
         .count()
 }
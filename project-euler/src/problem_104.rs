@@ -1,46 +1,41 @@
 use crate::util::arithmetic::Digits;
-use crate::util::sequences::fibonacci;
-use num_bigint::BigUint;
+use crate::util::sequences::leading_digits;
 
-fn check(x: &BigUint) -> bool {
-    let start = || {
-        let mut digits = [
-            true, false, false, false, false, false, false, false, false, false,
-        ];
+const MODULO: u64 = 1_000_000_000;
 
-        for d in x.digits(10).take(9).map(usize::from) {
-            if std::mem::replace(&mut digits[d], true) {
-                return false;
-            }
-        }
+fn is_pandigital(x: u64) -> bool {
+    let mut digits = x.digits(10);
+    let mut seen = [false; 10];
 
-        true
-    };
-
-    let end = || {
-        let mut digits = [
-            true, false, false, false, false, false, false, false, false, false,
-        ];
-
-        for d in x.digits_rev(10).take(9).map(usize::from) {
-            if std::mem::replace(&mut digits[d], true) {
-                return false;
-            }
-        }
-
-        true
-    };
-
-    end() && start()
+    digits.len() == 9
+        && digits.all(|d| d != 0 && !std::mem::replace(&mut seen[usize::from(d)], true))
 }
 
-// An optimisation would be to truncate numbers with > 21 digits, but it was
-// more fun to optimize the `digits` function.
-pub fn solve() -> usize {
-    1 + fibonacci()
-        .enumerate()
-        .skip(100)
-        .find(|(_, x)| check(x))
+pub fn solve() -> u64 {
+    // The last digits are obtained by computing the sequence modulo 10^9, the
+    // first digits are only computed when the last ones are pandigital.
+    let (mut curr, mut next) = (0, 1);
+
+    (0..)
+        .find(|&n| {
+            let found = is_pandigital(curr) && is_pandigital(leading_digits(n, 9));
+            let sum = (curr + next) % MODULO;
+            curr = std::mem::replace(&mut next, sum);
+            found
+        })
         .unwrap()
-        .0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_pandigital() {
+        assert!(is_pandigital(123_456_789));
+        assert!(is_pandigital(918_273_645));
+        assert!(!is_pandigital(112_345_678));
+        assert!(!is_pandigital(12_345_678));
+        assert!(!is_pandigital(1_234_567_890));
+    }
 }
@@ -1,4 +1,4 @@
-use rayon::prelude::*;
+use crate::util::primes::factorize;
 
 // x⁻¹ + y⁻¹ = n⁻¹
 //     <=> x != n and y = (n⁻¹ - x⁻¹)⁻¹
@@ -16,16 +16,23 @@ use rayon::prelude::*;
 //             <=> x² - 2nx <= 0
 //             <=> x (x - 2n) <= 0 (note: x > 0)
 //             <=> x <= 2n
+//
+// ---
+//
+// Writing x = n + a, we get y = n (n + a) / a = n + n² / a, hence solutions
+// are in bijection with divisors a of n² such that a <= n.
+
+fn distinct_solutions(n: u64) -> u64 {
+    let nb_divisors_square: u64 = factorize(n)
+        .into_iter()
+        .map(|(_, exp)| 2 * u64::from(exp) + 1)
+        .product();
 
-fn distinct_solutions(n: u64) -> usize {
-    (n + 1..=2 * n).filter(|x| n * x % (x - n) == 0).count()
+    nb_divisors_square.div_ceil(2)
 }
 
 pub fn solve() -> u64 {
-    (1..)
-        .par_bridge()
-        .find_first(|n| distinct_solutions(*n) > 1_000)
-        .unwrap()
+    (1..).find(|n| distinct_solutions(*n) > 1_000).unwrap()
 }
 
 #[cfg(test)]
@@ -1,32 +1,12 @@
-use fxhash::FxHashMap;
+use crate::util::dp::{count_k_subsets, Capped};
 
 fn get_sum(set: &[usize], sum_size: usize) -> usize {
-    // sums[nb][x] is defined if `x` is the sum of `nb` elements read from from
-    // the set so far, and it is set to `true` if only one such sum is known.
-    let mut sums: Vec<FxHashMap<usize, bool>> = vec![Default::default(); sum_size];
-
-    for &x in set {
-        for nb in (0..sum_size - 1).rev() {
-            // prev = &sums[nb], next = &mut sums[nb + 1]
-            let (prev, next) = {
-                let (head, tail) = sums.split_at_mut(nb + 1);
-                (&head[nb], &mut tail[0])
-            };
-
-            for (y, unique) in prev {
-                next.entry(x + y)
-                    .and_modify(|unique| *unique = false)
-                    .or_insert(*unique);
-            }
-        }
-
-        sums[0].insert(x, true);
-    }
-
-    sums[sum_size - 1]
-        .iter()
-        .filter(|(_, unique)| **unique)
-        .map(|(x, _)| x)
+    // Counting up to 2 is enough to know if a sum is unique
+    count_k_subsets::<Capped<2>>(set, sum_size)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, ways)| ways.0 == 1)
+        .map(|(sum, _)| sum)
         .sum()
 }
 
@@ -1,50 +1,40 @@
-// const STEP: u128 = 1504170715041707;
-// const LIMIT: u128 = 4503599627370517;
-//
-// fn u(n: u128) -> u128 {
-//     (n * STEP) % LIMIT
-// }
-//
-// fn next_coin(prev: u128) -> Option<u128> {
-//     fn search_next_coin(step: u128, limit: u128, max: u128) -> Option<u128> {
-//         if limit == 1 {
-//             None
-//         } else if step < limit {
-//             Some(step)
-//         } else {
-//             let shift = max - (max / step) * step;
-//             search_next_coin(shift, limit, step).map(|c| limit - c)
-//         }
-//     }
-//
-//     search_next_coin(STEP, prev, LIMIT)
-// }
-//
-// pub fn solve() -> u128 {
-//     let mut limit = 4503599627370517;
-//     let mut step = 1504170715041707;
-//     let mut max = LIMIT;
-//     let mut n = 1;
-//
-//     std::iter::from_fn(move || {
-//         if max > 1 {
-//             while u(n) >= max {
-//                 println!("n = {}", n);
-//                 // We need to walk at least LIMIT - u(n) until we walk past 0 again.
-//                 // This means ceil((LIMIT - u(n)) / STEP) steps.
-//                 n += (LIMIT - u(n) + STEP - 1) / STEP;
-//             }
-//
-//             max = u(n);
-//             Some(u(n))
-//         } else {
-//             None
-//         }
-//     })
-//     .inspect(|x| println!("{}", x))
-//     .sum()
-// }
-
-pub fn solve() -> &'static str {
-    "todo"
+use crate::util::modular::{mod_inverse, MulMod};
+
+const STEP: u64 = 1_504_170_715_041_707;
+const MODULUS: u64 = 4_503_599_627_370_517;
+
+// Coins are first searched by walking through the sequence, until they get
+// small enough to efficiently find the remaining ones by enumerating values
+// and computing their index in the sequence.
+const THRESHOLD: u64 = 100_000_000;
+
+pub fn solve() -> u64 {
+    let mut coins_sum = 0;
+    let mut last_coin = MODULUS;
+    let mut value = 0;
+
+    while last_coin > THRESHOLD {
+        value = (value + STEP) % MODULUS;
+
+        if value < last_coin {
+            last_coin = value;
+            coins_sum += value;
+        }
+    }
+
+    // A value v is a coin iff it appears before any smaller value, its index
+    // in the sequence is given by v * STEP⁻¹.
+    let inverse = mod_inverse(&STEP, &MODULUS).unwrap();
+    let mut min_index = MODULUS;
+
+    for value in 1..last_coin {
+        let index = value.mul_mod(&inverse, &MODULUS);
+
+        if index < min_index {
+            min_index = index;
+            coins_sum += value;
+        }
+    }
+
+    coins_sum
 }
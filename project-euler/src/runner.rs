@@ -0,0 +1,555 @@
+use std::io::Read;
+use std::ops::RangeInclusive;
+use std::process::{Child, Command, Output, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use colored::Colorize;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+
+const USAGE: &str = "\
+usage: project-euler [OPTIONS] [PROBLEMS...]
+
+Problems can be given as numbers (12) or inclusive ranges (1-50, 100-). If any
+problem is given, skipped problems in the selection are run.
+
+options:
+    -a, --all            also run problems that are skipped by default
+    -s, --skipped        only run problems that are skipped by default
+    -r, --repeat N       run each problem N times and report min/median time
+    -t, --timeout SECS   kill problems running for longer than SECS
+    -j, --jobs N         run up to N problems in parallel (default: 1)
+        --json           output results as JSON
+    -h, --help           print this message
+
+Answers are checked against a registry of hashes: ✓ means that the answer is
+correct, ✗ that it is wrong and ? that it is unknown. The exit code is 1 if any
+answer is wrong.";
+
+// Hidden argument used to run a single problem in a child process, which can
+// be killed if it exceeds its timeout.
+const WORKER_ARG: &str = "--worker";
+
+// ---
+// --- Problems
+// ---
+
+pub struct Problem {
+    pub name: &'static str,
+    pub skip: bool,
+    /// SHA-256 of the expected answer, if it is known.
+    pub answer_hash: Option<&'static str>,
+    pub solve: fn() -> String,
+}
+
+/// Hexadecimal SHA-256 of an answer, as stored in the registry.
+pub fn hash_answer(answer: &str) -> String {
+    Sha256::digest(answer.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+impl Problem {
+    pub fn number(&self) -> u32 {
+        self.name
+            .trim_start_matches("problem_")
+            .parse()
+            .expect("problem names should be of the form problem_XXX")
+    }
+
+    /// Check an answer against the registry, returns None if the expected
+    /// answer is unknown.
+    fn check(&self, answer: &str) -> Option<bool> {
+        self.answer_hash.map(|hash| hash_answer(answer) == hash)
+    }
+
+    fn run(&self, repeat: usize) -> Outcome {
+        let times: Vec<_> = (0..repeat)
+            .map(|_| {
+                let start = Instant::now();
+                let answer = (self.solve)();
+                (answer, start.elapsed())
+            })
+            .collect();
+
+        Outcome::Solved {
+            answer: times[0].0.clone(),
+            times: times.into_iter().map(|(_, time)| time).collect(),
+        }
+    }
+
+    fn run_in_child(&self, repeat: usize, timeout: Duration) -> Outcome {
+        let spawned = std::env::current_exe().and_then(|exe| {
+            Command::new(exe)
+                .args([WORKER_ARG, self.name, &repeat.to_string()])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+        });
+
+        let child = match spawned {
+            Ok(child) => child,
+            Err(err) => return Outcome::Failed(format!("could not spawn worker: {}", err)),
+        };
+
+        let output = match wait_with_timeout(child, timeout) {
+            Ok(Some(output)) => output,
+            Ok(None) => return Outcome::Timeout(timeout),
+            Err(err) => return Outcome::Failed(err.to_string()),
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if !output.status.success() {
+            let message = stderr.lines().find(|line| !line.is_empty());
+            return Outcome::Failed(message.unwrap_or("worker crashed").to_string());
+        }
+
+        // The worker prints the answer followed by the duration of each run
+        let mut lines = stdout.lines();
+        let answer = lines.next().unwrap_or_default().to_string();
+
+        let times = lines
+            .map(|line| line.parse().map(Duration::from_nanos))
+            .collect::<Result<_, _>>();
+
+        match times {
+            Ok(times) => Outcome::Solved { answer, times },
+            Err(_) => Outcome::Failed("invalid worker output".to_string()),
+        }
+    }
+}
+
+/// Read a pipe until it is closed from a separate thread.
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut buf).ok();
+        }
+
+        buf
+    })
+}
+
+/// Wait for a child to exit and collect its output, or kill it and return None
+/// if it is still running after `timeout`. Pipes are drained while waiting so
+/// that the child never blocks on writing to a full pipe.
+fn wait_with_timeout(mut child: Child, timeout: Duration) -> std::io::Result<Option<Output>> {
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let deadline = Instant::now() + timeout;
+
+    let status = loop {
+        match child.try_wait()? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                child.kill().ok();
+                child.wait()?;
+                return Ok(None);
+            }
+            None => std::thread::sleep(Duration::from_millis(1)),
+        }
+    };
+
+    Ok(Some(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    }))
+}
+
+// ---
+// --- Outcome
+// ---
+
+enum Outcome {
+    Solved {
+        answer: String,
+        times: Vec<Duration>,
+    },
+    Skipped,
+    Timeout(Duration),
+    Failed(String),
+}
+
+impl Outcome {
+    fn min_time(&self) -> Option<Duration> {
+        match self {
+            Self::Solved { times, .. } => times.iter().min().copied(),
+            _ => None,
+        }
+    }
+
+    fn median_time(&self) -> Option<Duration> {
+        match self {
+            Self::Solved { times, .. } => {
+                let mut times = times.clone();
+                times.sort_unstable();
+                times.get(times.len() / 2).copied()
+            }
+            _ => None,
+        }
+    }
+
+    fn is_wrong(&self, problem: &Problem) -> bool {
+        match self {
+            Self::Solved { answer, .. } => problem.check(answer) == Some(false),
+            _ => false,
+        }
+    }
+
+    fn print(&self, problem: &Problem) {
+        let mark = |answer: &str| match problem.check(answer) {
+            Some(true) => "✓".green(),
+            Some(false) => "✗".red(),
+            None => "?".dimmed(),
+        };
+
+        match self {
+            Self::Solved { answer, times } if times.len() == 1 => {
+                println!(
+                    "{}: {:<20} {} ({:.0?})",
+                    problem.name,
+                    answer,
+                    mark(answer),
+                    times[0]
+                );
+            }
+            Self::Solved { answer, .. } => {
+                println!(
+                    "{}: {:<20} {} (min {:.0?}, median {:.0?})",
+                    problem.name,
+                    answer,
+                    mark(answer),
+                    self.min_time().unwrap(),
+                    self.median_time().unwrap(),
+                );
+            }
+            Self::Skipped => {
+                println!("{}", format!("{}: skipped", problem.name).dimmed());
+            }
+            Self::Timeout(timeout) => {
+                let msg = format!("{}: timeout after {:.0?}", problem.name, timeout);
+                println!("{}", msg.red());
+            }
+            Self::Failed(err) => {
+                println!("{}", format!("{}: failed ({})", problem.name, err).red());
+            }
+        }
+    }
+
+    fn to_json(&self, problem: &Problem) -> String {
+        let status = match self {
+            Self::Solved { .. } => "solved",
+            Self::Skipped => "skipped",
+            Self::Timeout(_) => "timeout",
+            Self::Failed(_) => "failed",
+        };
+
+        let mut fields = vec![
+            format!("\"problem\":{}", problem.number()),
+            format!("\"name\":{}", json_string(problem.name)),
+            format!("\"status\":{}", json_string(status)),
+        ];
+
+        match self {
+            Self::Solved { answer, times } => {
+                let times: Vec<_> = times.iter().map(|t| t.as_nanos().to_string()).collect();
+                let verified = match problem.check(answer) {
+                    Some(verified) => verified.to_string(),
+                    None => "null".to_string(),
+                };
+
+                fields.push(format!("\"answer\":{}", json_string(answer)));
+                fields.push(format!("\"verified\":{}", verified));
+                fields.push(format!("\"times_ns\":[{}]", times.join(",")));
+                fields.push(format!(
+                    "\"min_ns\":{}",
+                    self.min_time().unwrap().as_nanos()
+                ));
+                fields.push(format!(
+                    "\"median_ns\":{}",
+                    self.median_time().unwrap().as_nanos()
+                ));
+            }
+            Self::Timeout(timeout) => {
+                fields.push(format!("\"timeout_ns\":{}", timeout.as_nanos()));
+            }
+            Self::Failed(err) => fields.push(format!("\"error\":{}", json_string(err))),
+            Self::Skipped => {}
+        }
+
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if c.is_control() => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+
+    res.push('"');
+    res
+}
+
+// ---
+// --- Command line
+// ---
+
+struct Options {
+    selection: Vec<RangeInclusive<u32>>,
+    all: bool,
+    skipped_only: bool,
+    repeat: usize,
+    timeout: Option<Duration>,
+    jobs: usize,
+    json: bool,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Self {
+            selection: Vec::new(),
+            all: false,
+            skipped_only: false,
+            repeat: 1,
+            timeout: None,
+            jobs: 1,
+            json: false,
+        };
+
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("missing value for {}", arg))
+            };
+
+            match arg.as_str() {
+                "-a" | "--all" => options.all = true,
+                "-s" | "--skipped" => options.skipped_only = true,
+                "-r" | "--repeat" => options.repeat = parse_positive(value()?, arg)?,
+                "-j" | "--jobs" => options.jobs = parse_positive(value()?, arg)?,
+                "--json" => options.json = true,
+                "-t" | "--timeout" => {
+                    let value = value()?;
+
+                    let secs: f64 = value
+                        .parse()
+                        .ok()
+                        .filter(|secs| *secs > 0.0 && f64::is_finite(*secs))
+                        .ok_or_else(|| format!("invalid timeout: {}", value))?;
+
+                    options.timeout = Some(Duration::from_secs_f64(secs));
+                }
+                _ if arg.starts_with('-') => return Err(format!("unknown option: {}", arg)),
+                _ => options.selection.push(parse_range(arg)?),
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn should_run(&self, problem: &Problem) -> bool {
+        !problem.skip || self.all || self.skipped_only || !self.selection.is_empty()
+    }
+
+    fn is_selected(&self, problem: &Problem) -> bool {
+        let number = problem.number();
+        self.selection.is_empty() || self.selection.iter().any(|r| r.contains(&number))
+    }
+}
+
+fn parse_positive(value: &str, arg: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|x| *x > 0)
+        .ok_or_else(|| format!("invalid value for {}: {}", arg, value))
+}
+
+fn parse_range(arg: &str) -> Result<RangeInclusive<u32>, String> {
+    let parse = |x: &str| x.parse().map_err(|_| format!("invalid problem: {}", arg));
+
+    match arg.split_once('-') {
+        None => parse(arg).map(|x| x..=x),
+        Some((start, "")) => Ok(parse(start)?..=u32::MAX),
+        Some((start, end)) => Ok(parse(start)?..=parse(end)?),
+    }
+}
+
+// ---
+// --- Entry point
+// ---
+
+pub fn main(problems: &[Problem]) {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+
+    if let [worker, name, repeat] = args.as_slice() {
+        if worker == WORKER_ARG {
+            return run_worker(problems, name, repeat);
+        }
+    }
+
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let options = Options::parse(&args).unwrap_or_else(|err| {
+        eprintln!("error: {}\n\n{}", err, USAGE);
+        std::process::exit(2)
+    });
+
+    let selected: Vec<_> = problems
+        .iter()
+        .filter(|problem| options.is_selected(problem))
+        .filter(|problem| !options.skipped_only || problem.skip)
+        .collect();
+
+    let run = |problem: &Problem| {
+        if !options.should_run(problem) {
+            Outcome::Skipped
+        } else if let Some(timeout) = options.timeout {
+            problem.run_in_child(options.repeat, timeout)
+        } else {
+            problem.run(options.repeat)
+        }
+    };
+
+    let report = |problem: &Problem, outcome: &Outcome| {
+        if !options.json {
+            outcome.print(problem)
+        }
+    };
+
+    let outcomes: Vec<_> = if options.jobs == 1 {
+        selected
+            .iter()
+            .map(|problem| {
+                let outcome = run(problem);
+                report(problem, &outcome);
+                outcome
+            })
+            .collect()
+    } else {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(options.jobs)
+            .build()
+            .expect("could not build thread pool");
+
+        let outcomes: Vec<_> = pool.install(|| selected.par_iter().map(|pb| run(pb)).collect());
+
+        for (problem, outcome) in selected.iter().zip(&outcomes) {
+            report(problem, outcome);
+        }
+
+        outcomes
+    };
+
+    if options.json {
+        let results: Vec<_> = (selected.iter().zip(&outcomes))
+            .map(|(problem, outcome)| outcome.to_json(problem))
+            .collect();
+
+        println!("[{}]", results.join(","));
+    }
+
+    if (selected.iter().zip(&outcomes)).any(|(problem, outcome)| outcome.is_wrong(problem)) {
+        std::process::exit(1);
+    }
+}
+
+fn run_worker(problems: &[Problem], name: &str, repeat: &str) {
+    let problem = problems
+        .iter()
+        .find(|problem| problem.name == name)
+        .expect("unknown problem");
+
+    if let Outcome::Solved { answer, times } = problem.run(repeat.parse().unwrap()) {
+        println!("{}", answer);
+
+        for time in times {
+            println!("{}", time.as_nanos());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_options() {
+        let args: Vec<_> = ["1-10", "12", "700-", "-r", "3", "--json", "-t", "0.5"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+
+        let options = Options::parse(&args).unwrap();
+        assert_eq!(options.selection, [1..=10, 12..=12, 700..=u32::MAX]);
+        assert_eq!(options.repeat, 3);
+        assert_eq!(options.timeout, Some(Duration::from_millis(500)));
+        assert!(options.json);
+
+        let parse = |args: &[&str]| {
+            let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+            Options::parse(&args).err()
+        };
+
+        assert!(parse(&["-j"]).is_some());
+        assert!(parse(&["-r", "0"]).is_some());
+        assert!(parse(&["-t", "-1"]).is_some());
+        assert!(parse(&["--foo"]).is_some());
+        assert!(parse(&["1-a"]).is_some());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_wait_with_timeout() {
+        let spawn = |script: &str| {
+            Command::new("sh")
+                .args(["-c", script])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .unwrap()
+        };
+
+        // Outputs larger than the pipe buffers don't block the child
+        let child = spawn("head -c 200000 /dev/zero; head -c 100000 /dev/zero >&2");
+        let output = wait_with_timeout(child, Duration::from_secs(10)).unwrap();
+        let output = output.expect("child should not time out");
+        assert!(output.status.success());
+        assert_eq!(output.stdout.len(), 200_000);
+        assert_eq!(output.stderr.len(), 100_000);
+
+        let child = spawn("exec sleep 10");
+        let output = wait_with_timeout(child, Duration::from_millis(50)).unwrap();
+        assert!(output.is_none());
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("abc"), "\"abc\"");
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+        assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+    }
+}
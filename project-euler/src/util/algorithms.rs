@@ -1,19 +1,22 @@
 use std::cmp::{Ord, Ordering};
-use std::convert::From;
-use std::ops::{Add, Mul};
-
-use num_traits::Zero;
-
-use crate::util::arithmetic::{Digits, Factorial};
 
 // ---
 // --- Permutations
 // ---
 
+/// Rearrange `nums` into the next permutation in lexicographic order, returns
+/// false and sorts `nums` if it was the last one.
 pub fn next_permutation<T: Ord>(nums: &mut [T]) -> bool {
     if let Some(last_ascending) = nums.windows(2).rposition(|w| w[0] < w[1]) {
+        // The suffix is non-increasing, find its last item greater than pivot
         let swap_with = nums[last_ascending + 1..]
-            .binary_search_by(|n| nums[last_ascending].cmp(&n).then(Ordering::Less))
+            .binary_search_by(|n| {
+                if *n > nums[last_ascending] {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            })
             .unwrap_err();
         nums.swap(last_ascending, last_ascending + swap_with);
         nums[last_ascending + 1..].reverse();
@@ -24,23 +27,733 @@ pub fn next_permutation<T: Ord>(nums: &mut [T]) -> bool {
     }
 }
 
-pub trait Permutations {
-    fn permutations(self) -> Box<dyn Iterator<Item = Self>>;
+/// Iterator over ordered selections of `k` items of a slice, in lexicographic
+/// order of positions.
+pub struct KPermutations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    k: usize,
+    done: bool,
+}
+
+impl<T: Clone> Iterator for KPermutations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let res = self.indices[..self.k]
+            .iter()
+            .map(|&i| self.items[i].clone())
+            .collect();
+
+        // Unselected indices are kept sorted, reversing them makes the next
+        // permutation of the whole array the next selection.
+        self.indices[self.k..].reverse();
+        self.done = !next_permutation(&mut self.indices);
+        Some(res)
+    }
+}
+
+pub fn k_permutations<T>(items: &[T], k: usize) -> KPermutations<'_, T> {
+    KPermutations {
+        items,
+        indices: (0..items.len()).collect(),
+        k,
+        done: k > items.len(),
+    }
+}
+
+pub fn permutations<T>(items: &[T]) -> KPermutations<'_, T> {
+    k_permutations(items, items.len())
+}
+
+// ---
+// --- Factorial number system
+// ---
+
+/// Rank of a sequence of distinct items among all its permutations sorted in
+/// lexicographic order, panics if the rank doesn't fit into a `u128`.
+pub fn permutation_rank<T: Ord>(perm: &[T]) -> u128 {
+    let mut rank: u128 = 0;
+    // factorial of the number of items after position i, or None if it overflows
+    let mut factorial = Some(1u128);
+
+    for i in (0..perm.len()).rev() {
+        let smaller_after = perm[i + 1..].iter().filter(|x| **x < perm[i]).count();
+
+        if smaller_after > 0 {
+            rank = factorial
+                .and_then(|fact| fact.checked_mul(smaller_after as u128))
+                .and_then(|term| rank.checked_add(term))
+                .expect("rank overflows a u128");
+        }
+
+        factorial = factorial.and_then(|fact| fact.checked_mul((perm.len() - i) as u128));
+    }
+
+    rank
+}
+
+/// Permutation of `items` of given rank in the lexicographic order of
+/// positions, returns None if the rank exceeds the number of permutations.
+pub fn nth_permutation<T: Clone>(items: &[T], mut rank: u128) -> Option<Vec<T>> {
+    // factorials[i] is i!, or None if it overflows
+    let factorials: Vec<Option<u128>> = (0..=items.len())
+        .scan(Some(1u128), |acc, i| {
+            let res = *acc;
+            *acc = acc.and_then(|acc: u128| acc.checked_mul(i as u128 + 1));
+            Some(res)
+        })
+        .collect();
+
+    if factorials[items.len()].map(|count| rank >= count) == Some(true) {
+        return None;
+    }
+
+    let mut remaining: Vec<_> = items.iter().collect();
+
+    let res = (0..items.len())
+        .rev()
+        .map(|i| {
+            let digit = factorials[i].map(|fact| rank / fact).unwrap_or(0);
+            rank -= digit * factorials[i].unwrap_or(0);
+            remaining.remove(digit as usize).clone()
+        })
+        .collect();
+
+    Some(res)
+}
+
+// ---
+// --- Combinations
+// ---
+
+/// Iterator over subsets of `k` items of a slice, each given in the order of
+/// the slice, in lexicographic order of positions.
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl<T: Clone> Iterator for Combinations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let res = self
+            .indices
+            .iter()
+            .map(|&i| self.items[i].clone())
+            .collect();
+        let (n, k) = (self.items.len(), self.indices.len());
+
+        match (0..k).rposition(|i| self.indices[i] < n - k + i) {
+            Some(i) => {
+                self.indices[i] += 1;
+
+                for j in i + 1..k {
+                    self.indices[j] = self.indices[j - 1] + 1;
+                }
+            }
+            None => self.done = true,
+        }
+
+        Some(res)
+    }
+}
+
+pub fn combinations<T>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations {
+        items,
+        indices: (0..k).collect(),
+        done: k > items.len(),
+    }
+}
+
+/// Iterator over multisets of `k` items of a slice, each given in the order
+/// of the slice, in lexicographic order of positions.
+pub struct CombinationsWithReplacement<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl<T: Clone> Iterator for CombinationsWithReplacement<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let res = self
+            .indices
+            .iter()
+            .map(|&i| self.items[i].clone())
+            .collect();
+        let last = self.items.len().saturating_sub(1);
+
+        match self.indices.iter().rposition(|&i| i < last) {
+            Some(i) => {
+                let next = self.indices[i] + 1;
+                self.indices[i..].fill(next);
+            }
+            None => self.done = true,
+        }
+
+        Some(res)
+    }
+}
+
+pub fn combinations_with_replacement<T>(
+    items: &[T],
+    k: usize,
+) -> CombinationsWithReplacement<'_, T> {
+    CombinationsWithReplacement {
+        items,
+        indices: vec![0; k],
+        done: items.is_empty() && k > 0,
+    }
+}
+
+/// Iterate over all subsets of a slice, by increasing size.
+pub fn subsets<T: Clone>(items: &[T]) -> impl Iterator<Item = Vec<T>> + '_ {
+    (0..=items.len()).flat_map(move |k| combinations(items, k))
+}
+
+// ---
+// --- Integer partitions
+// ---
+
+/// Iterator over partitions of an integer as non-increasing lists of parts,
+/// in reverse lexicographic order.
+pub struct Partitions {
+    parts: Vec<u64>,
+    done: bool,
+}
+
+impl Iterator for Partitions {
+    type Item = Vec<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let res = self.parts.clone();
+
+        // Gather trailing ones with the last part greater than one and split
+        // them into parts as large as possible.
+        let mut rest: u64 = 0;
+
+        while self.parts.last() == Some(&1) {
+            self.parts.pop();
+            rest += 1;
+        }
+
+        match self.parts.last_mut() {
+            Some(last) => {
+                *last -= 1;
+                let size = *last;
+                rest += 1;
+
+                while rest >= size {
+                    self.parts.push(size);
+                    rest -= size;
+                }
+
+                if rest > 0 {
+                    self.parts.push(rest);
+                }
+            }
+            None => self.done = true,
+        }
+
+        Some(res)
+    }
+}
+
+pub fn partitions(n: u64) -> Partitions {
+    Partitions {
+        parts: if n == 0 { Vec::new() } else { vec![n] },
+        done: false,
+    }
 }
 
-impl<T> Permutations for T
-where
-    T: Add<Self, Output = Self> + Mul<Self, Output = Self> + Clone + Digits + From<u8> + Zero,
-{
-    fn permutations(self) -> Box<dyn Iterator<Item = Self>> {
-        let mut digits: Vec<_> = self.digits(10).collect();
+// ---
+// --- Exact cover
+// ---
+
+/// Exact cover problem solved with Knuth's dancing links: rows are subsets of
+/// columns, and solutions are sets of rows covering each primary column
+/// exactly once and each secondary column at most once.
+pub struct ExactCover {
+    // Node 0 is the root, nodes 1..=nb_columns are column headers and other
+    // nodes are items of rows, linked in circular lists.
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    row: Vec<usize>,
+    // Number of rows in each column, indexed by header
+    size: Vec<usize>,
+    nb_rows: usize,
+}
+
+impl ExactCover {
+    /// Problem with columns `0..nb_primary` that must be covered, followed by
+    /// `nb_secondary` columns that are optional.
+    pub fn new(nb_primary: usize, nb_secondary: usize) -> Self {
+        let nb_headers = nb_primary + nb_secondary + 1;
+        let ids: Vec<_> = (0..nb_headers).collect();
+
+        let mut res = Self {
+            left: ids.clone(),
+            right: ids.clone(),
+            up: ids.clone(),
+            down: ids.clone(),
+            column: ids,
+            row: vec![usize::MAX; nb_headers],
+            size: vec![0; nb_headers],
+            nb_rows: 0,
+        };
+
+        // Only primary columns are linked to the root
+        for header in 0..=nb_primary {
+            res.right[header] = (header + 1) % (nb_primary + 1);
+            res.left[(header + 1) % (nb_primary + 1)] = header;
+        }
+
+        res
+    }
+
+    /// Add a row covering given columns and return its index.
+    pub fn add_row(&mut self, columns: &[usize]) -> usize {
+        let first = self.left.len();
+
+        for (i, &col) in columns.iter().enumerate() {
+            let header = col + 1;
+            assert!(header < self.size.len(), "unknown column {}", col);
+            let node = first + i;
+
+            self.left.push(if i == 0 {
+                first + columns.len() - 1
+            } else {
+                node - 1
+            });
+            self.right.push(if i + 1 == columns.len() {
+                first
+            } else {
+                node + 1
+            });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            self.column.push(header);
+            self.row.push(self.nb_rows);
+
+            let last = self.up[header];
+            self.down[last] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+        }
+
+        self.nb_rows += 1;
+        self.nb_rows - 1
+    }
+
+    fn cover(&mut self, header: usize) {
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = right;
+        self.left[right] = left;
+
+        let mut i = self.down[header];
+
+        while i != header {
+            let mut j = self.right[i];
+
+            while j != i {
+                let (up, down) = (self.up[j], self.down[j]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut i = self.up[header];
+
+        while i != header {
+            let mut j = self.left[i];
+
+            while j != i {
+                let (up, down) = (self.up[j], self.down[j]);
+                self.down[up] = j;
+                self.up[down] = j;
+                self.size[self.column[j]] += 1;
+                j = self.left[j];
+            }
+
+            i = self.up[i];
+        }
+
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = header;
+        self.left[right] = header;
+    }
+
+    // Cover or uncover other columns of the row of a node
+    fn select(&mut self, node: usize) {
+        let mut j = self.right[node];
+
+        while j != node {
+            self.cover(self.column[j]);
+            j = self.right[j];
+        }
+    }
+
+    fn unselect(&mut self, node: usize) {
+        let mut j = self.left[node];
+
+        while j != node {
+            self.uncover(self.column[j]);
+            j = self.left[j];
+        }
+    }
+
+    /// Iterate over solutions, given as indices of rows.
+    pub fn solutions(&mut self) -> ExactCoverSolutions<'_> {
+        ExactCoverSolutions {
+            problem: self,
+            stack: Vec::new(),
+            backtrack: false,
+            done: false,
+        }
+    }
+
+    pub fn first_solution(&mut self) -> Option<Vec<usize>> {
+        self.solutions().next()
+    }
+
+    pub fn count_solutions(&mut self) -> usize {
+        self.solutions().count()
+    }
+}
+
+/// Iterator over solutions of an exact cover problem, which is restored to its
+/// initial state when the iterator is dropped.
+pub struct ExactCoverSolutions<'a> {
+    problem: &'a mut ExactCover,
+    // Selected node for each level of the search
+    stack: Vec<usize>,
+    backtrack: bool,
+    done: bool,
+}
+
+impl Iterator for ExactCoverSolutions<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pb = &mut *self.problem;
+
+        while !self.done {
+            if self.backtrack {
+                // Move to the next row of the deepest column that has one
+                match self.stack.pop() {
+                    None => self.done = true,
+                    Some(node) => {
+                        pb.unselect(node);
+                        let next = pb.down[node];
+
+                        if next == pb.column[node] {
+                            pb.uncover(next);
+                        } else {
+                            pb.select(next);
+                            self.stack.push(next);
+                            self.backtrack = false;
+                        }
+                    }
+                }
+
+                continue;
+            }
+
+            if pb.right[0] == 0 {
+                self.backtrack = true;
+                return Some(self.stack.iter().map(|&node| pb.row[node]).collect());
+            }
+
+            // Branch over the column with fewest rows
+            let mut header = pb.right[0];
+            let mut best = header;
+
+            while header != 0 {
+                if pb.size[header] < pb.size[best] {
+                    best = header;
+                }
+
+                header = pb.right[header];
+            }
+
+            if pb.size[best] == 0 {
+                self.backtrack = true;
+                continue;
+            }
+
+            pb.cover(best);
+            let node = pb.down[best];
+            pb.select(node);
+            self.stack.push(node);
+        }
+
+        None
+    }
+}
+
+impl Drop for ExactCoverSolutions<'_> {
+    fn drop(&mut self) {
+        while let Some(node) = self.stack.pop() {
+            self.problem.unselect(node);
+            self.problem.uncover(self.problem.column[node]);
+        }
+    }
+}
+
+/// Fill a Sudoku grid where empty cells are set to 0, returns None if the
+/// grid has no solution. Panics if a cell is greater than 9.
+pub fn solve_sudoku(grid: &[[u8; 9]; 9]) -> Option<[[u8; 9]; 9]> {
+    // Columns are cells, then digits in rows, in columns and in boxes
+    let mut problem = ExactCover::new(4 * 81, 0);
+    let mut choices = Vec::new();
+
+    for (r, line) in grid.iter().enumerate() {
+        for (c, &given) in line.iter().enumerate() {
+            assert!(given <= 9, "invalid Sudoku cell: {}", given);
+            let digits = if given == 0 { 1..=9 } else { given..=given };
+
+            for digit in digits {
+                let d = usize::from(digit) - 1;
+                let b = 3 * (r / 3) + c / 3;
+                problem.add_row(&[9 * r + c, 81 + 9 * r + d, 162 + 9 * c + d, 243 + 9 * b + d]);
+                choices.push((r, c, digit));
+            }
+        }
+    }
+
+    let mut res = *grid;
+
+    for row in problem.first_solution()? {
+        let (r, c, digit) = choices[row];
+        res[r][c] = digit;
+    }
+
+    Some(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_next_permutation() {
+        let mut nums = [1, 2, 2, 3];
+        let mut count = 1;
+
+        while next_permutation(&mut nums) {
+            count += 1;
+        }
+
+        assert_eq!(count, 12);
+        assert_eq!(nums, [1, 2, 2, 3]);
+    }
+
+    #[test]
+    fn test_permutations() {
+        let perms: Vec<_> = permutations(&['a', 'b', 'c']).collect();
+        assert_eq!(perms.len(), 6);
+        assert_eq!(perms[0], ['a', 'b', 'c']);
+        assert_eq!(perms[1], ['a', 'c', 'b']);
+        assert_eq!(perms[5], ['c', 'b', 'a']);
+
+        let perms: Vec<_> = k_permutations(&[3, 1, 2], 2).collect();
+        assert_eq!(perms, [[3, 1], [3, 2], [1, 3], [1, 2], [2, 3], [2, 1]]);
+
+        assert_eq!(k_permutations(&[1, 2], 0).count(), 1);
+        assert_eq!(k_permutations(&[1, 2], 3).count(), 0);
+        assert_eq!(k_permutations(&[0; 6], 3).count(), 120);
+    }
+
+    #[test]
+    fn test_rank() {
+        let items: Vec<_> = (0..5).collect();
+
+        for (rank, perm) in permutations(&items).enumerate() {
+            assert_eq!(permutation_rank(&perm), rank as u128);
+            assert_eq!(nth_permutation(&items, rank as u128), Some(perm));
+        }
+
+        assert_eq!(nth_permutation(&items, 120), None);
+        assert_eq!(nth_permutation::<u8>(&[], 0), Some(vec![]));
+
+        let items: Vec<_> = (0..40).collect();
+        let perm = nth_permutation(&items, u128::MAX).unwrap();
+        assert_eq!(
+            permutation_rank(&perm[10..]),
+            u128::MAX % (1..=30).product::<u128>()
+        );
+
+        // Factorials overflow while the rank is small
+        let mut perm: Vec<_> = (0..50).collect();
+        assert_eq!(permutation_rank(&perm), 0);
+        perm.swap(48, 49);
+        assert_eq!(permutation_rank(&perm), 1);
+        assert_eq!(permutation_rank(&perm[..35]), 0);
+    }
+
+    #[test]
+    fn test_combinations() {
+        let combs: Vec<_> = combinations(&[1, 2, 3, 4], 2).collect();
+        assert_eq!(combs, [[1, 2], [1, 3], [1, 4], [2, 3], [2, 4], [3, 4]]);
+        assert_eq!(combinations(&[1, 2, 3], 0).count(), 1);
+        assert_eq!(combinations(&[1, 2, 3], 4).count(), 0);
+        assert_eq!(combinations(&[0; 10], 4).count(), 210);
+
+        let combs: Vec<_> = combinations_with_replacement(&[1, 2, 3], 2).collect();
+        assert_eq!(combs, [[1, 1], [1, 2], [1, 3], [2, 2], [2, 3], [3, 3]]);
+        assert_eq!(combinations_with_replacement::<u8>(&[], 0).count(), 1);
+        assert_eq!(combinations_with_replacement::<u8>(&[], 2).count(), 0);
+        assert_eq!(combinations_with_replacement(&[0; 5], 3).count(), 35);
+
+        let subs: Vec<_> = subsets(&[1, 2, 3]).collect();
+        assert_eq!(subs.len(), 8);
+        assert_eq!(subs[0], []);
+        assert_eq!(subs[4], [1, 2]);
+        assert_eq!(subs[7], [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_exact_cover() {
+        // Example from Knuth's paper, with columns A to G
+        let mut problem = ExactCover::new(7, 0);
+
+        for row in [
+            &[2, 4, 5][..],
+            &[0, 3, 6],
+            &[1, 2, 5],
+            &[0, 3],
+            &[1, 6],
+            &[3, 4, 6],
+        ] {
+            problem.add_row(row);
+        }
+
+        let mut solution = problem.first_solution().unwrap();
+        solution.sort_unstable();
+        assert_eq!(solution, [0, 3, 4]);
+        assert_eq!(problem.count_solutions(), 1);
+
+        // Without any primary column, the empty set is the only solution
+        assert_eq!(
+            ExactCover::new(0, 2).solutions().collect::<Vec<_>>(),
+            [vec![]]
+        );
+        assert_eq!(ExactCover::new(1, 0).count_solutions(), 0);
+
+        // N-queens, where diagonals are secondary columns
+        let queens = |n: usize| {
+            let mut problem = ExactCover::new(2 * n, 2 * (2 * n - 1));
+
+            for r in 0..n {
+                for c in 0..n {
+                    let diag = 2 * n + r + c;
+                    let anti_diag = 2 * n + (2 * n - 1) + (n - 1 + r - c);
+                    problem.add_row(&[r, n + c, diag, anti_diag]);
+                }
+            }
+
+            problem.count_solutions()
+        };
+
+        assert_eq!(queens(1), 1);
+        assert_eq!(queens(3), 0);
+        assert_eq!(queens(6), 4);
+        assert_eq!(queens(8), 92);
+    }
+
+    #[test]
+    fn test_sudoku() {
+        let parse = |lines: [&str; 9]| -> [[u8; 9]; 9] {
+            lines.map(|line| {
+                let mut row = [0; 9];
+
+                for (cell, byte) in row.iter_mut().zip(line.bytes()) {
+                    *cell = if byte == b'.' { 0 } else { byte - b'0' };
+                }
+
+                row
+            })
+        };
+
+        let grid = parse([
+            "53..7....",
+            "6..195...",
+            ".98....6.",
+            "8...6...3",
+            "4..8.3..1",
+            "7...2...6",
+            ".6....28.",
+            "...419..5",
+            "....8..79",
+        ]);
+
+        let solution = parse([
+            "534678912",
+            "672195348",
+            "198342567",
+            "859761423",
+            "426853791",
+            "713924856",
+            "961537284",
+            "287419635",
+            "345286179",
+        ]);
+
+        assert_eq!(solve_sudoku(&grid), Some(solution));
+        assert_eq!(solve_sudoku(&solution), Some(solution));
+
+        let mut invalid = grid;
+        invalid[0][2] = 5;
+        assert_eq!(solve_sudoku(&invalid), None);
+    }
 
-        Box::new((0..(digits.len() as u128).factorial()).map(move |_| {
-            next_permutation(&mut digits);
+    #[test]
+    fn test_partitions() {
+        let parts: Vec<_> = partitions(5).collect();
+        assert_eq!(
+            parts,
+            [
+                vec![5],
+                vec![4, 1],
+                vec![3, 2],
+                vec![3, 1, 1],
+                vec![2, 2, 1],
+                vec![2, 1, 1, 1],
+                vec![1, 1, 1, 1, 1]
+            ]
+        );
 
-            digits
-                .iter()
-                .fold(Self::zero(), |sum, d| sum * Self::from(10) + Self::from(*d))
-        }))
+        assert_eq!(partitions(0).collect::<Vec<_>>(), [vec![]]);
+        assert_eq!(partitions(30).count(), 5604);
     }
 }
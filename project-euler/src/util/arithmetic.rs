@@ -1,11 +1,11 @@
-use std::cmp::Ord;
-use std::convert::TryInto;
-use std::fmt::Debug;
-use std::ops::{Div, DivAssign, Rem, Sub};
+use std::iter::Rev;
+use std::ops::RangeInclusive;
 
 use num_bigint::BigUint;
-use num_integer::Roots;
-use num_traits::{One, Pow, Zero};
+use num_traits::{One, Zero};
+
+use crate::util::multiplicative::divisors_from_factors;
+use crate::util::primes::factorize_u128;
 
 // ---
 // --- Factorial
@@ -55,10 +55,15 @@ pub trait NbDigits {
 macro_rules! impl_nb_digits_for_primitives {
     ( $type: ty ) => {
         impl NbDigits for $type {
-            type Output = u8;
+            type Output = u32;
 
             fn nb_digits(&self, base: u8) -> Self::Output {
-               1 + ((*self as f64).log2() / (base as f64).log2()) as u8
+                debug_assert!(base > 1);
+
+                match self.checked_ilog(base as $type) {
+                    Some(log) => log + 1,
+                    None => 1,
+                }
             }
         }
     };
@@ -73,7 +78,27 @@ impl NbDigits for BigUint {
     type Output = u64;
 
     fn nb_digits(&self, base: u8) -> Self::Output {
-        1 + (self.bits() as f64 / f64::from(base).log2()) as u64
+        debug_assert!(base > 1);
+
+        if self.is_zero() {
+            return 1;
+        }
+
+        // The floating point estimation is only off by one at most, fix it
+        // with exact comparisons.
+        let log_base = f64::from(base).log2();
+        let base = BigUint::from(base);
+        let mut log = ((self.bits() - 1) as f64 / log_base) as u64;
+
+        while log > 0 && base.pow(log as u32) > *self {
+            log -= 1;
+        }
+
+        while base.pow(log as u32 + 1) <= *self {
+            log += 1;
+        }
+
+        log + 1
     }
 }
 
@@ -81,68 +106,142 @@ impl NbDigits for BigUint {
 // --- Digits
 // ---
 
-pub trait Digits {
-    fn digits(&self, base: u8) -> Box<dyn Iterator<Item = u8> + '_>;
-    fn digits_rev(&self, base: u8) -> Box<dyn Iterator<Item = u8> + '_>;
-}
-
-impl<T> Digits for T
-where
-    T: Clone
-        + DivAssign<Self>
-        + Div<Self, Output = Self>
-        + From<u8>
-        + TryInto<u8>
-        + NbDigits
-        + One
-        + Ord
-        + Pow<<Self as NbDigits>::Output, Output = Self>
-        + Rem<Self, Output = Self>
-        + Zero,
-    <T as TryInto<u8>>::Error: Debug,
-    <T as NbDigits>::Output: One + Sub<<T as NbDigits>::Output, Output = <T as NbDigits>::Output>,
-{
-    fn digits(&self, base: u8) -> Box<dyn Iterator<Item = u8> + '_> {
-        debug_assert!(base > 0);
+/// Iterator over the digits of a primitive integer, from most to least
+/// significant, which doesn't allocate.
+#[derive(Clone, Debug)]
+pub struct DigitsIter<T> {
+    // Remaining digits of the number
+    value: T,
+    // Weight of the most significant remaining digit
+    high: T,
+    base: T,
+    len: usize,
+}
 
-        if self.is_zero() {
-            return Box::new(std::iter::once(0u8));
+macro_rules! impl_digits_for_primitives {
+    ( $type: ty ) => {
+        impl Iterator for DigitsIter<$type> {
+            type Item = u8;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                if self.len == 0 {
+                    return None;
+                }
+
+                let digit = self.value / self.high;
+                self.value %= self.high;
+                self.high /= self.base;
+                self.len -= 1;
+                Some(digit as u8)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
         }
 
-        let mut curr_digit = Self::from(base).pow(self.nb_digits(base) - One::one());
-
-        Box::new(std::iter::from_fn(move || {
-            if !curr_digit.is_zero() {
-                let res = ((self.clone() / curr_digit.clone()) % Self::from(base))
-                    .try_into()
-                    .expect("digits should fit into u8");
-                curr_digit /= base.into();
-                Some(res)
-            } else {
-                None
+        impl DoubleEndedIterator for DigitsIter<$type> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                if self.len == 0 {
+                    return None;
+                }
+
+                let digit = self.value % self.base;
+                self.value /= self.base;
+                self.high /= self.base;
+                self.len -= 1;
+                Some(digit as u8)
             }
-        })) as _
-    }
+        }
 
-    fn digits_rev(&self, base: u8) -> Box<dyn Iterator<Item = u8> + '_> {
-        debug_assert!(base > 0);
+        impl ExactSizeIterator for DigitsIter<$type> {}
 
-        if self.is_zero() {
-            return Box::new(std::iter::once(0u8));
+        impl Digits for $type {
+            type Iter = DigitsIter<$type>;
+
+            fn digits(&self, base: u8) -> Self::Iter {
+                debug_assert!(base > 1);
+                let len = self.nb_digits(base);
+
+                DigitsIter {
+                    value: *self,
+                    high: (base as $type).pow(len - 1),
+                    base: base.into(),
+                    len: len as usize,
+                }
+            }
         }
 
-        let mut curr = self.clone();
+        impl FromDigits for $type {
+            fn from_digits(digits: impl IntoIterator<Item = u8>, base: u8) -> Self {
+                digits
+                    .into_iter()
+                    .fold(0, |acc, d| acc * Self::from(base) + Self::from(d))
+            }
+        }
+    };
+    ( $( $type: ty ),* ) => {
+        $( impl_digits_for_primitives!($type); )*
+    };
+}
+
+impl_digits_for_primitives!(u8, u16, u32, u64, u128, usize);
+
+impl Digits for BigUint {
+    type Iter = std::vec::IntoIter<u8>;
+
+    fn digits(&self, base: u8) -> Self::Iter {
+        debug_assert!(base > 1);
+        self.to_radix_be(base.into()).into_iter()
+    }
+}
+
+impl FromDigits for BigUint {
+    fn from_digits(digits: impl IntoIterator<Item = u8>, base: u8) -> Self {
+        let digits: Vec<_> = digits.into_iter().collect();
+        BigUint::from_radix_be(&digits, base.into()).expect("invalid digit")
+    }
+}
+
+pub trait Digits {
+    type Iter: DoubleEndedIterator<Item = u8> + ExactSizeIterator;
+
+    /// Digits from most to least significant, zero has a single digit.
+    fn digits(&self, base: u8) -> Self::Iter;
+
+    fn digits_rev(&self, base: u8) -> Rev<Self::Iter> {
+        self.digits(base).rev()
+    }
 
-        Box::new(std::iter::from_fn(move || {
-            if !curr.is_zero() {
-                let res = curr.clone() % Self::from(base);
-                curr /= base.into();
-                Some(res.try_into().expect("digits should fit into u8"))
-            } else {
-                None
+    fn digit_sum(&self, base: u8) -> u64 {
+        self.digits(base).map(u64::from).sum()
+    }
+
+    fn is_palindrome(&self, base: u8) -> bool {
+        let mut digits = self.digits(base);
+
+        while let (Some(x), Some(y)) = (digits.next(), digits.next_back()) {
+            if x != y {
+                return false;
             }
-        }))
+        }
+
+        true
     }
+
+    /// Number written with the same digits in reverse order, trailing zeros
+    /// are dropped.
+    fn reverse_digits(&self, base: u8) -> Self
+    where
+        Self: FromDigits + Sized,
+    {
+        Self::from_digits(self.digits_rev(base), base)
+    }
+}
+
+pub trait FromDigits {
+    /// Build a number from its digits, from most to least significant.
+    fn from_digits(digits: impl IntoIterator<Item = u8>, base: u8) -> Self;
 }
 
 // ---
@@ -154,37 +253,52 @@ pub trait Divisors {
 }
 
 macro_rules! impl_divisors_for_primitives {
-    () => {};
-    ( $type: ty $( , $other: ty )* ) => {
+    ( $type: ty ) => {
         impl Divisors for $type {
             fn divisors(self: $type) -> Box<dyn Iterator<Item = $type>> {
-                let sqrt = self.sqrt();
-
-                Box::new(
-                    ((1..=sqrt).filter(move |d| self % d == 0))
-                        .chain({
-                            if self % sqrt == 0 && sqrt * sqrt != self {
-                                Some(self / sqrt)
-                            } else {
-                                None
-                            }
-                        })
-                        .chain(
-                            (1..sqrt)
-                                .rev()
-                                .filter(move |d| self % d == 0)
-                                .map(move |d| self / d),
-                        ),
-                )
+                let factors: Vec<_> = factorize_u128(self as u128)
+                    .into_iter()
+                    .map(|(p, exp)| (p as $type, exp))
+                    .collect();
+
+                Box::new(divisors_from_factors(&factors).into_iter())
             }
         }
-
-        impl_divisors_for_primitives!($( $other ),*);
+    };
+    ( $( $type: ty ),* ) => {
+        $( impl_divisors_for_primitives!($type); )*
     };
 }
 
 impl_divisors_for_primitives!(u8, u16, u32, u64, u128, usize);
 
+// ---
+// --- Floor quotients
+// ---
+
+/// Iterator over maximal blocks of integers i in `1..=n` such that n / i is
+/// constant, given along with this quotient. There are at most 2√n blocks.
+pub struct FloorBlocks {
+    n: u64,
+    next: Option<u64>,
+}
+
+impl Iterator for FloorBlocks {
+    type Item = (RangeInclusive<u64>, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let lo = self.next.filter(|&lo| lo <= self.n)?;
+        let quotient = self.n / lo;
+        let hi = self.n / quotient;
+        self.next = hi.checked_add(1);
+        Some((lo..=hi, quotient))
+    }
+}
+
+pub fn floor_blocks(n: u64) -> FloorBlocks {
+    FloorBlocks { n, next: Some(1) }
+}
+
 // ---
 // --- Tests
 // ---
@@ -202,6 +316,16 @@ mod test {
         assert_eq!(1_000_000_u32.nb_digits(10), 7);
         assert_eq!(0b0_u32.nb_digits(2), 1);
         assert_eq!(0b111_u32.nb_digits(2), 3);
+        assert_eq!(999_999_999_999_999_999_u64.nb_digits(10), 18);
+        assert_eq!(1_000_000_000_000_000_000_u64.nb_digits(10), 19);
+        assert_eq!(u64::MAX.nb_digits(10), 20);
+        assert_eq!(u128::MAX.nb_digits(10), 39);
+        assert_eq!((u128::MAX / 10).nb_digits(10), 38);
+        assert_eq!(BigUint::zero().nb_digits(10), 1);
+        assert_eq!(BigUint::from(u128::MAX).nb_digits(10), 39);
+        assert_eq!(BigUint::from(10u8).pow(100u32).nb_digits(10), 101);
+        assert_eq!((BigUint::from(10u8).pow(100u32) - 1u8).nb_digits(10), 100);
+        assert_eq!(BigUint::from(255u8).nb_digits(16), 2);
     }
 
     #[test]
@@ -240,4 +364,87 @@ mod test {
             [6, 5, 9, 0, 8, 1]
         );
     }
+
+    #[test]
+    fn test_digits_both_ends() {
+        let mut digits = 12345u32.digits(10);
+        assert_eq!(digits.len(), 5);
+        assert_eq!(digits.next(), Some(1));
+        assert_eq!(digits.next_back(), Some(5));
+        assert_eq!(digits.next(), Some(2));
+        assert_eq!(digits.next_back(), Some(4));
+        assert_eq!(digits.collect::<Vec<_>>(), [3]);
+
+        assert_eq!(u64::MAX.digits(16).collect::<Vec<_>>(), [15; 16]);
+        assert_eq!(0xabu8.digits(16).collect::<Vec<_>>(), [10, 11]);
+    }
+
+    #[test]
+    fn test_digit_helpers() {
+        assert_eq!(u32::from_digits([1, 0, 5, 6], 10), 1056);
+        assert_eq!(u8::from_digits([1, 0, 1], 2), 5);
+        assert_eq!(u64::from_digits([], 10), 0);
+        assert_eq!(BigUint::from_digits([1, 8, 0], 10), BigUint::from(180u8));
+
+        assert_eq!(1234u32.digit_sum(10), 10);
+        assert_eq!(BigUint::from(u128::MAX).digit_sum(2), 128);
+
+        assert_eq!(1230u32.reverse_digits(10), 321);
+        assert_eq!(0b1101u8.reverse_digits(2), 0b1011);
+        assert_eq!(
+            BigUint::from(1234u32).reverse_digits(10),
+            BigUint::from(4321u32)
+        );
+
+        assert!(0u32.is_palindrome(10));
+        assert!(585u32.is_palindrome(10));
+        assert!(585u32.is_palindrome(2));
+        assert!(!586u32.is_palindrome(10));
+        assert!(1221u64.is_palindrome(10));
+        assert!(!10u64.is_palindrome(10));
+        assert!(BigUint::from(12321u32).is_palindrome(10));
+    }
+
+    #[test]
+    fn test_floor_blocks() {
+        let blocks: Vec<_> = floor_blocks(10).collect();
+        assert_eq!(
+            blocks,
+            [(1..=1, 10), (2..=2, 5), (3..=3, 3), (4..=5, 2), (6..=10, 1)]
+        );
+
+        assert_eq!(floor_blocks(0).count(), 0);
+
+        // The last block ends at the maximal integer
+        let last_blocks = FloorBlocks {
+            n: u64::MAX,
+            next: Some(u64::MAX / 3 + 1),
+        };
+
+        assert_eq!(
+            last_blocks.collect::<Vec<_>>(),
+            [
+                (u64::MAX / 3 + 1..=u64::MAX / 2, 2),
+                (u64::MAX / 2 + 1..=u64::MAX, 1)
+            ]
+        );
+
+        for n in 1..200 {
+            let expanded: Vec<_> = floor_blocks(n)
+                .flat_map(|(block, quotient)| block.map(move |i| (i, quotient)))
+                .collect();
+
+            assert_eq!(expanded, (1..=n).map(|i| (i, n / i)).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(1u32.divisors().collect::<Vec<_>>(), [1]);
+        assert_eq!(28u8.divisors().collect::<Vec<_>>(), [1, 2, 4, 7, 14, 28]);
+        assert_eq!(
+            36usize.divisors().collect::<Vec<_>>(),
+            [1, 2, 3, 4, 6, 9, 12, 18, 36]
+        );
+    }
 }
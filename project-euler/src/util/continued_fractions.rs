@@ -0,0 +1,257 @@
+use std::ops::{Add, Mul};
+
+use num_bigint::BigUint;
+use num_integer::{Integer, Roots};
+use num_rational::Ratio;
+
+// ---
+// --- Quadratic irrationals
+// ---
+
+/// Continued fraction expansion of a quadratic irrational (p + √d) / q, which
+/// is periodic. If `d` is a perfect square, the number is rational and the
+/// expansion is finite.
+#[derive(Clone, Debug)]
+pub struct QuadraticContinuedFraction {
+    p: i128,
+    q: i128,
+    d: i128,
+    sqrt_d: i128,
+}
+
+impl QuadraticContinuedFraction {
+    pub fn new(p: i64, d: u64, q: i64) -> Self {
+        assert!(q != 0, "denominator must be non-zero");
+        let (mut p, mut d, mut q) = (i128::from(p), i128::from(d), i128::from(q));
+
+        // The recurrence requires q to divide d - p², which can be ensured
+        // by multiplying all terms by |q|.
+        if (d - p * p) % q != 0 {
+            p *= q.abs();
+            d *= q * q;
+            q *= q.abs();
+        }
+
+        let sqrt_d = d.sqrt();
+        Self { p, q, d, sqrt_d }
+    }
+
+    pub fn sqrt(n: u64) -> Self {
+        Self::new(0, n, 1)
+    }
+
+    fn is_rational(&self) -> bool {
+        self.sqrt_d * self.sqrt_d == self.d
+    }
+}
+
+impl Iterator for QuadraticContinuedFraction {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.q == 0 {
+            return None;
+        }
+
+        if self.is_rational() {
+            // Expansion of (p + √d) / q using euclid's algorithm, with d set to
+            // 0 for the next iterations.
+            let num = self.p + self.sqrt_d;
+            self.d = 0;
+            self.sqrt_d = 0;
+            let a = Integer::div_floor(&num, &self.q);
+            self.p = self.q;
+            self.q = num - a * self.q;
+            return Some(a as i64);
+        }
+
+        // As √d is irrational, floor((p + √d) / q) only depends of floor(√d)
+        let a = if self.q > 0 {
+            Integer::div_floor(&(self.p + self.sqrt_d), &self.q)
+        } else {
+            Integer::div_floor(&(self.p + self.sqrt_d + 1), &self.q)
+        };
+
+        self.p = a * self.q - self.p;
+        self.q = (self.d - self.p * self.p) / self.q;
+        Some(a as i64)
+    }
+}
+
+/// Continued fraction of √n, given as its integer part followed by the period
+/// of the expansion, which is empty if n is a perfect square.
+pub fn sqrt_continued_fraction(n: u64) -> (u64, Vec<u64>) {
+    let mut terms = QuadraticContinuedFraction::sqrt(n).map(|a| a as u64);
+    let a0 = terms.next().unwrap();
+
+    let period = if a0 * a0 == n {
+        Vec::new()
+    } else {
+        let mut period: Vec<_> = terms.take_while(|&a| a != 2 * a0).collect();
+        period.push(2 * a0);
+        period
+    };
+
+    (a0, period)
+}
+
+// ---
+// --- Convergents
+// ---
+
+/// Iterate over the convergents h_n / k_n of a continued fraction.
+pub fn convergents<T>(terms: impl IntoIterator<Item = T>) -> impl Iterator<Item = Ratio<T>>
+where
+    T: Clone + Integer,
+    for<'a> &'a T: Add<&'a T, Output = T> + Mul<&'a T, Output = T>,
+{
+    let (mut h_prev, mut h) = (T::zero(), T::one());
+    let (mut k_prev, mut k) = (T::one(), T::zero());
+
+    terms.into_iter().map(move |a| {
+        let h_next = &(&a * &h) + &h_prev;
+        let k_next = &(&a * &k) + &k_prev;
+        h_prev = std::mem::replace(&mut h, h_next);
+        k_prev = std::mem::replace(&mut k, k_next);
+        Ratio::new_raw(h.clone(), k.clone())
+    })
+}
+
+// ---
+// --- Pell equation
+// ---
+
+/// Fundamental solution (x, y) of x² - d·y² = 1, or of x² - d·y² = -1 if
+/// `negative` is set. Returns None if there is no solution.
+pub fn pell_fundamental(d: u64, negative: bool) -> Option<(BigUint, BigUint)> {
+    let (a0, period) = sqrt_continued_fraction(d);
+
+    if period.is_empty() || (negative && period.len() % 2 == 0) {
+        return None;
+    }
+
+    // The convergent preceding the end of the first period solves the
+    // equation for (-1)^len(period), iterate over a second period if needed.
+    let nb_periods = if negative || period.len() % 2 == 0 {
+        1
+    } else {
+        2
+    };
+    let nb_terms = nb_periods * period.len();
+
+    let terms = std::iter::once(a0)
+        .chain(period.into_iter().cycle())
+        .map(BigUint::from);
+
+    let solution = convergents(terms).nth(nb_terms - 1)?;
+    Some(solution.into())
+}
+
+/// Iterate over all positive solutions of x² - d·y² = 1, or of x² - d·y² = -1
+/// if `negative` is set, in increasing order.
+pub fn pell_solutions(d: u64, negative: bool) -> impl Iterator<Item = (BigUint, BigUint)> {
+    let fundamental = pell_fundamental(d, negative);
+    let step = pell_fundamental(d, false);
+
+    fundamental
+        .zip(step)
+        .into_iter()
+        .flat_map(move |(first, (x1, y1))| {
+            // Next solution is given by (x + y√d) * (x1 + y1√d)
+            std::iter::successors(Some(first), move |(x, y)| {
+                let next_x = x * &x1 + d * (y * &y1);
+                let next_y = x * &y1 + y * &x1;
+                Some((next_x, next_y))
+            })
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sqrt_continued_fraction() {
+        assert_eq!(sqrt_continued_fraction(2), (1, vec![2]));
+        assert_eq!(sqrt_continued_fraction(13), (3, vec![1, 1, 1, 1, 6]));
+        assert_eq!(sqrt_continued_fraction(23), (4, vec![1, 3, 1, 8]));
+        assert_eq!(sqrt_continued_fraction(25), (5, vec![]));
+    }
+
+    #[test]
+    fn test_quadratic_continued_fraction() {
+        // Golden ratio
+        let terms: Vec<_> = QuadraticContinuedFraction::new(1, 5, 2).take(5).collect();
+        assert_eq!(terms, [1, 1, 1, 1, 1]);
+
+        let terms: Vec<_> = QuadraticContinuedFraction::new(1, 2, 3).take(6).collect();
+        assert_eq!(terms, [0, 1, 4, 8, 4, 8]);
+
+        let terms: Vec<_> = QuadraticContinuedFraction::new(-1, 2, 3).take(6).collect();
+        assert_eq!(terms, [0, 7, 4, 8, 4, 8]);
+
+        let terms: Vec<_> = QuadraticContinuedFraction::new(3, 7, -2).take(6).collect();
+        assert_eq!(terms, [-3, 5, 1, 1, 1, 4]);
+
+        // Rationals: 415 / 93 = [4; 2, 6, 7]
+        let terms: Vec<_> = QuadraticContinuedFraction::new(400, 225, 93).collect();
+        assert_eq!(terms, [4, 2, 6, 7]);
+
+        let terms: Vec<_> = QuadraticContinuedFraction::new(-2, 0, 3).collect();
+        assert_eq!(terms, [-1, 3]);
+    }
+
+    #[test]
+    fn test_convergents() {
+        let e_terms = [2, 1, 2, 1, 1, 4, 1, 1, 6, 1];
+        let tenth = convergents(e_terms.iter().map(|&a| BigUint::from(a as u32))).last();
+        assert_eq!(tenth, Some(Ratio::new_raw(1457u32.into(), 536u32.into())));
+
+        let sqrt_2: Vec<_> = convergents(QuadraticContinuedFraction::sqrt(2))
+            .take(4)
+            .collect();
+
+        assert_eq!(
+            sqrt_2,
+            [
+                Ratio::new(1, 1),
+                Ratio::new(3, 2),
+                Ratio::new(7, 5),
+                Ratio::new(17, 12)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pell() {
+        let solution = |x: u64, y: u64| Some((BigUint::from(x), BigUint::from(y)));
+        assert_eq!(pell_fundamental(2, false), solution(3, 2));
+        assert_eq!(pell_fundamental(2, true), solution(1, 1));
+        assert_eq!(pell_fundamental(3, true), None);
+        assert_eq!(pell_fundamental(7, false), solution(8, 3));
+        assert_eq!(pell_fundamental(13, false), solution(649, 180));
+        assert_eq!(pell_fundamental(13, true), solution(18, 5));
+        assert_eq!(pell_fundamental(16, false), None);
+
+        assert_eq!(
+            pell_fundamental(61, false),
+            solution(1_766_319_049, 226_153_980)
+        );
+
+        let solutions: Vec<_> = pell_solutions(2, false).take(3).collect();
+        assert_eq!(
+            solutions,
+            [
+                solution(3, 2).unwrap(),
+                solution(17, 12).unwrap(),
+                solution(99, 70).unwrap()
+            ]
+        );
+
+        for (x, y) in pell_solutions(13, true).take(5) {
+            assert_eq!(&x * &x + 1u8, 13u8 * &y * &y);
+        }
+
+        assert_eq!(pell_solutions(9, false).count(), 0);
+    }
+}
@@ -0,0 +1,320 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::util::linalg::Vector;
+
+// ---
+// --- Errors
+// ---
+
+/// Error raised when a data file doesn't have the expected format, pointing
+/// to the (1-indexed) line that could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: line + 1,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Non-empty lines of the input, along with their index.
+fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    (input.lines().enumerate())
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+/// Parse values of a line separated by commas or whitespaces.
+fn values<T: FromStr>(line_idx: usize, line: &str) -> Result<Vec<T>, ParseError> {
+    (line.split(|c: char| c == ',' || c.is_whitespace()))
+        .filter(|raw| !raw.is_empty())
+        .map(|raw| {
+            raw.parse()
+                .map_err(|_| ParseError::new(line_idx, format!("invalid value: {:?}", raw)))
+        })
+        .collect()
+}
+
+// ---
+// --- Parsers
+// ---
+
+/// Parse a list of double-quoted words separated by commas, such as
+/// `"MARY","PATRICIA"`, which may span over several lines.
+pub fn quoted_words(input: &str) -> Result<Vec<&str>, ParseError> {
+    lines(input)
+        .flat_map(|(i, line)| line.split(',').map(move |raw| (i, raw.trim())))
+        .filter(|(_, raw)| !raw.is_empty())
+        .map(|(i, raw)| {
+            raw.strip_prefix('"')
+                .and_then(|raw| raw.strip_suffix('"'))
+                .filter(|word| !word.contains('"'))
+                .ok_or_else(|| ParseError::new(i, format!("expected a quoted word: {}", raw)))
+        })
+        .collect()
+}
+
+/// Parse a triangle of numbers where the n-th row contains n numbers.
+pub fn number_triangle<T: FromStr>(input: &str) -> Result<Vec<Vec<T>>, ParseError> {
+    lines(input)
+        .enumerate()
+        .map(|(row, (i, line))| {
+            let values = values(i, line)?;
+
+            if values.len() != row + 1 {
+                return Err(ParseError::new(
+                    i,
+                    format!("expected {} numbers in row, got {}", row + 1, values.len()),
+                ));
+            }
+
+            Ok(values)
+        })
+        .collect()
+}
+
+/// Parse a matrix with one row per line, where values are separated by commas
+/// or whitespaces and all rows have the same length.
+pub fn matrix<T: FromStr>(input: &str) -> Result<Vec<Vec<T>>, ParseError> {
+    let mut width = None;
+
+    lines(input)
+        .map(|(i, line)| {
+            let row = values(i, line)?;
+
+            match *width.get_or_insert(row.len()) {
+                w if w != row.len() => Err(ParseError::new(
+                    i,
+                    format!("expected {} columns, got {}", w, row.len()),
+                )),
+                _ => Ok(row),
+            }
+        })
+        .collect()
+}
+
+/// Parse a list of points for each line, given as a flat list of coordinates
+/// such as `x1,y1,x2,y2`.
+pub fn coordinates<T: FromStr, const N: usize>(
+    input: &str,
+) -> Result<Vec<Vec<Vector<T, N>>>, ParseError> {
+    lines(input)
+        .map(|(i, line)| {
+            let mut values = values(i, line)?.into_iter();
+
+            if values.len() % N != 0 {
+                return Err(ParseError::new(
+                    i,
+                    format!(
+                        "expected points of dimension {}, got {} values",
+                        N,
+                        values.len()
+                    ),
+                ));
+            }
+
+            Ok((0..values.len() / N)
+                .map(|_| Vector::from([(); N].map(|_| values.next().unwrap())))
+                .collect())
+        })
+        .collect()
+}
+
+/// Parse a ciphertext given as a list of bytes separated by commas.
+pub fn ciphertext(input: &str) -> Result<Vec<u8>, ParseError> {
+    lines(input)
+        .map(|(i, line)| values::<u8>(i, line))
+        .try_fold(Vec::new(), |mut acc, bytes| {
+            acc.extend(bytes?);
+            Ok(acc)
+        })
+}
+
+/// Parse Sudoku grids, each of them introduced by a header line such as
+/// `Grid 01` and followed by nine lines of nine digits where 0 denotes an
+/// empty cell.
+pub fn sudoku_grids(input: &str) -> Result<Vec<[[u8; 9]; 9]>, ParseError> {
+    let mut grids = Vec::new();
+    let mut lines = lines(input);
+
+    while let Some((i, header)) = lines.next() {
+        if !header.starts_with("Grid") {
+            return Err(ParseError::new(
+                i,
+                format!("expected a grid header: {}", header),
+            ));
+        }
+
+        let mut grid = [[0; 9]; 9];
+
+        for row in &mut grid {
+            let (i, line) = lines
+                .next()
+                .ok_or_else(|| ParseError::new(i, "unexpected end of grid"))?;
+
+            if line.len() != 9 || !line.bytes().all(|c| c.is_ascii_digit()) {
+                return Err(ParseError::new(i, format!("expected 9 digits: {}", line)));
+            }
+
+            for (cell, c) in row.iter_mut().zip(line.bytes()) {
+                *cell = c - b'0';
+            }
+        }
+
+        grids.push(grid);
+    }
+
+    Ok(grids)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn err(line: usize, message: &str) -> ParseError {
+        ParseError {
+            line,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_quoted_words() {
+        assert_eq!(quoted_words("\"A\",\"BC\"\n"), Ok(vec!["A", "BC"]));
+        assert_eq!(quoted_words("\"A\",\n\"B\""), Ok(vec!["A", "B"]));
+        assert_eq!(quoted_words(""), Ok(vec![]));
+        assert_eq!(
+            quoted_words("\"A\"\n\"B\",C"),
+            Err(err(2, "expected a quoted word: C"))
+        );
+
+        let names = quoted_words(include_str!("../data/problem_022.txt")).unwrap();
+        assert_eq!(names.len(), 5163);
+        assert_eq!(names[0], "MARY");
+    }
+
+    #[test]
+    fn test_number_triangle() {
+        assert_eq!(
+            number_triangle::<u64>("3\n7 4\n2 4 6\n"),
+            Ok(vec![vec![3], vec![7, 4], vec![2, 4, 6]])
+        );
+        assert_eq!(
+            number_triangle::<u64>("3\n7 4 1\n"),
+            Err(err(2, "expected 2 numbers in row, got 3"))
+        );
+        assert_eq!(
+            number_triangle::<u64>("3\n7 x\n"),
+            Err(err(2, "invalid value: \"x\""))
+        );
+
+        let triangle = number_triangle::<u64>(include_str!("../data/problem_067.txt")).unwrap();
+        assert_eq!(triangle.len(), 100);
+    }
+
+    #[test]
+    fn test_matrix() {
+        assert_eq!(
+            matrix::<i32>("1,2\n3 -4\n\n"),
+            Ok(vec![vec![1, 2], vec![3, -4]])
+        );
+        assert_eq!(
+            matrix::<i32>("1,2\n3\n"),
+            Err(err(2, "expected 2 columns, got 1"))
+        );
+    }
+
+    #[test]
+    fn test_coordinates() {
+        assert_eq!(
+            coordinates::<i64, 2>("1,2,3,4\n-5,6\n"),
+            Ok(vec![vec![v![1, 2], v![3, 4]], vec![v![-5, 6]]])
+        );
+        assert_eq!(
+            coordinates::<i64, 3>("1,2,3,4\n"),
+            Err(err(1, "expected points of dimension 3, got 4 values"))
+        );
+
+        let triangles = coordinates::<i64, 2>(include_str!("../data/problem_102.txt")).unwrap();
+        assert_eq!(triangles.len(), 1000);
+        assert!(triangles.iter().all(|t| t.len() == 3));
+    }
+
+    #[test]
+    fn test_ciphertext() {
+        assert_eq!(ciphertext("36,22,80\n,0,\n"), Ok(vec![36, 22, 80, 0]));
+        assert_eq!(ciphertext("36,256"), Err(err(1, "invalid value: \"256\"")));
+    }
+
+    #[test]
+    fn test_sudoku_grids() {
+        let input = "Grid 01\n".to_string() + &"123456789\n".repeat(9) + "Grid 02\n";
+        assert_eq!(sudoku_grids(&input), Err(err(11, "unexpected end of grid")));
+
+        let grids = sudoku_grids(&input[..input.len() - 8]).unwrap();
+        assert_eq!(grids, [[[1, 2, 3, 4, 5, 6, 7, 8, 9]; 9]]);
+
+        assert_eq!(
+            sudoku_grids("Grid 01\n12345678\n"),
+            Err(err(2, "expected 9 digits: 12345678"))
+        );
+        assert_eq!(
+            sudoku_grids("123456789\n"),
+            Err(err(1, "expected a grid header: 123456789"))
+        );
+    }
+
+    #[test]
+    fn test_solve_loaded_sudoku() {
+        use crate::util::algorithms::solve_sudoku;
+
+        let input = "\
+Grid 01
+003020600
+900305001
+001806400
+008102900
+700000008
+006708200
+002609500
+800203009
+005010300
+";
+
+        let grids = sudoku_grids(input).unwrap();
+        let solution = solve_sudoku(&grids[0]).expect("grid should be solvable");
+        assert_eq!(solution[0][..3], [4, 8, 3]);
+
+        // Givens are kept and every row, column and box holds all digits
+        let mut givens = (grids[0].iter().flatten()).zip(solution.iter().flatten());
+        assert!(givens.all(|(&given, &cell)| given == 0 || given == cell));
+
+        for i in 0..9 {
+            let mut row: Vec<_> = (0..9).map(|j| solution[i][j]).collect();
+            let mut col: Vec<_> = (0..9).map(|j| solution[j][i]).collect();
+            let mut block: Vec<_> = (0..9)
+                .map(|j| solution[3 * (i / 3) + j / 3][3 * (i % 3) + j % 3])
+                .collect();
+
+            for digits in [&mut row, &mut col, &mut block] {
+                digits.sort_unstable();
+                assert_eq!(*digits, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+            }
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_rational::Rational64;
+use num_traits::{ToPrimitive, Zero};
+
+// ---
+// --- Square roots
+// ---
+
+/// Iterator over the digits of √n, starting with the digits of its integer
+/// part and followed by infinitely many fractional digits.
+pub struct SqrtDigits {
+    base: u64,
+    // Groups of two digits of n, from least to most significant.
+    pairs: Vec<u64>,
+    // Digits yielded so far, as an integer, and remainder of the root.
+    root: BigUint,
+    remainder: BigUint,
+}
+
+impl Iterator for SqrtDigits {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        // Bring down the next group, which is zero in the fractional part
+        let pair = self.pairs.pop().unwrap_or(0);
+        let current = &self.remainder * (self.base * self.base) + pair;
+
+        // Largest digit x such that (2·base·root + x)·x <= current
+        let double_root = &self.root * (2 * self.base);
+        let cost = |x: u64| (&double_root + x) * x;
+
+        let mut x = if self.root.is_zero() {
+            current.sqrt().to_u64().unwrap()
+        } else {
+            (&current / &double_root).to_u64().unwrap_or(u64::MAX)
+        }
+        .min(self.base - 1);
+
+        while cost(x) > current {
+            x -= 1;
+        }
+
+        self.remainder = current - cost(x);
+        self.root = &self.root * self.base + x;
+        Some(x as u8)
+    }
+}
+
+pub fn sqrt_digits(n: u64, base: u8) -> SqrtDigits {
+    assert!(base >= 2, "base must be at least 2");
+    let base = u64::from(base);
+
+    let mut pairs: Vec<_> = std::iter::successors(Some(n), |&n| Some(n / (base * base)))
+        .take_while(|&n| n > 0)
+        .map(|n| n % (base * base))
+        .collect();
+
+    if pairs.is_empty() {
+        pairs.push(0);
+    }
+
+    SqrtDigits {
+        base,
+        pairs,
+        root: BigUint::zero(),
+        remainder: BigUint::zero(),
+    }
+}
+
+// ---
+// --- Spigots
+// ---
+
+// Split the first item of a spigot, which is the integer part of the number
+// and may not be a digit in small bases, into digits.
+fn split_integer_part(mut spigot: impl Iterator<Item = u8>, base: u8) -> impl Iterator<Item = u8> {
+    let mut integer = spigot.next().expect("spigots are infinite");
+    let mut digits = Vec::new();
+
+    loop {
+        digits.push(integer % base);
+        integer /= base;
+
+        if integer == 0 {
+            break;
+        }
+    }
+
+    digits.into_iter().rev().chain(spigot)
+}
+
+/// Infinite stream of the digits of e, starting with the digits of its
+/// integer part. It composes maps x ↦ 1 + x/k following
+/// e = 1 + 1/1·(1 + 1/2·(1 + 1/3·(...))), where the tail of the expansion is
+/// always in [1, 2].
+pub fn e_digits(base: u8) -> impl Iterator<Item = u8> {
+    assert!(base >= 2, "base must be at least 2");
+    let big_base = BigInt::from(base);
+
+    // Current map is x ↦ (q·x + r) / t
+    let (mut q, mut r, mut t) = (BigInt::from(1), BigInt::zero(), BigInt::from(1));
+    let mut k = BigInt::from(1);
+
+    let spigot = std::iter::from_fn(move || loop {
+        let low = Integer::div_floor(&(&q + &r), &t);
+        let high = Integer::div_floor(&(&q * 2 + &r), &t);
+
+        if low == high {
+            r = (&r - &low * &t) * &big_base;
+            q *= &big_base;
+            return Some(low.to_u8().unwrap());
+        }
+
+        r = (&q + &r) * &k;
+        t *= &k;
+        k += 1;
+    });
+
+    split_integer_part(spigot, base)
+}
+
+/// Infinite stream of the digits of π, starting with the digits of its
+/// integer part, using the unbounded spigot algorithm of Gibbons.
+pub fn pi_digits(base: u8) -> impl Iterator<Item = u8> {
+    assert!(base >= 2, "base must be at least 2");
+    let big_base = BigInt::from(base);
+    let (mut q, mut r, mut t) = (BigInt::from(1), BigInt::zero(), BigInt::from(1));
+    let (mut k, mut n, mut l) = (BigInt::from(1), BigInt::from(3), BigInt::from(3));
+
+    let spigot = std::iter::from_fn(move || loop {
+        if &q * 4 + &r - &t < &n * &t {
+            let digit = n.to_u8().unwrap();
+            let next_n = Integer::div_floor(&((&q * 3 + &r) * &big_base), &t) - &n * &big_base;
+            r = (&r - &n * &t) * &big_base;
+            q *= &big_base;
+            n = next_n;
+            return Some(digit);
+        }
+
+        n = Integer::div_floor(&(&q * (&k * 7 + 2) + &r * &l), &(&t * &l));
+        r = (&q * 2 + &r) * &l;
+        q *= &k;
+        t *= &l;
+        k += 1;
+        l += 2;
+    });
+
+    split_integer_part(spigot, base)
+}
+
+// ---
+// --- Recurring expansions
+// ---
+
+/// Positional expansion of a rational number, where fractional digits are
+/// made of a pre-period followed by a period that repeats forever.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RationalExpansion {
+    pub negative: bool,
+    pub integer: u64,
+    pub pre_period: Vec<u8>,
+    pub period: Vec<u8>,
+}
+
+pub fn rational_expansion(x: &Rational64, base: u8) -> RationalExpansion {
+    assert!(base >= 2, "base must be at least 2");
+    let base = u128::from(base);
+    let numer = u128::from(x.numer().unsigned_abs());
+    let denom = u128::from(x.denom().unsigned_abs());
+
+    // The pre-period lasts until factors shared by the base and the
+    // denominator have been removed from it.
+    let mut pre_period_len = 0;
+    let mut rest = denom;
+
+    loop {
+        let gcd = rest.gcd(&base);
+
+        if gcd == 1 {
+            break;
+        }
+
+        rest /= gcd;
+        pre_period_len += 1;
+    }
+
+    let mut remainder = numer % denom;
+
+    let long_division = |remainder: &mut u128| {
+        *remainder *= base;
+        let digit = (*remainder / denom) as u8;
+        *remainder %= denom;
+        digit
+    };
+
+    let pre_period = (0..pre_period_len)
+        .map(|_| long_division(&mut remainder))
+        .collect();
+
+    // Remainders are now purely periodic
+    let start = remainder;
+    let mut period = Vec::new();
+
+    if start != 0 {
+        period.push(long_division(&mut remainder));
+
+        while remainder != start {
+            period.push(long_division(&mut remainder));
+        }
+    }
+
+    RationalExpansion {
+        negative: *x.numer() < 0,
+        integer: (numer / denom) as u64,
+        pre_period,
+        period,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn digits(iter: impl Iterator<Item = u8>, len: usize) -> String {
+        iter.take(len)
+            .map(|d| std::char::from_digit(d.into(), 36).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_sqrt_digits() {
+        assert_eq!(digits(sqrt_digits(2, 10), 20), "14142135623730950488");
+        assert_eq!(digits(sqrt_digits(0, 10), 3), "000");
+        assert_eq!(digits(sqrt_digits(16, 10), 4), "4000");
+        assert_eq!(digits(sqrt_digits(12_345, 10), 8), "11110805");
+        assert_eq!(digits(sqrt_digits(u64::MAX, 10), 12), "429496729599");
+        assert_eq!(digits(sqrt_digits(2, 2), 12), "101101010000");
+        assert_eq!(digits(sqrt_digits(3, 16), 10), "1bb67ae858");
+
+        let sum: u64 = sqrt_digits(2, 10).take(100).map(u64::from).sum();
+        assert_eq!(sum, 475);
+    }
+
+    #[test]
+    fn test_spigots() {
+        assert_eq!(digits(e_digits(10), 30), "271828182845904523536028747135");
+        assert_eq!(digits(e_digits(16), 12), "2b7e151628ae");
+        assert_eq!(digits(pi_digits(10), 30), "314159265358979323846264338327");
+        assert_eq!(digits(pi_digits(16), 12), "3243f6a8885a");
+        assert_eq!(digits(pi_digits(4), 6), "302100");
+        assert_eq!(digits(pi_digits(2), 13), "1100100100001");
+        assert_eq!(digits(pi_digits(3), 6), "100102");
+        assert_eq!(digits(e_digits(2), 11), "10101101111");
+
+        // The 1000th digit of each expansion
+        assert_eq!(e_digits(10).nth(1000), Some(4));
+        assert_eq!(pi_digits(10).nth(1000), Some(9));
+    }
+
+    #[test]
+    fn test_rational_expansion() {
+        let expansion = |p, q, base| {
+            let exp = rational_expansion(&Rational64::new(p, q), base);
+            (exp.negative, exp.integer, exp.pre_period, exp.period)
+        };
+
+        assert_eq!(expansion(1, 6, 10), (false, 0, vec![1], vec![6]));
+        assert_eq!(
+            expansion(1, 7, 10),
+            (false, 0, vec![], vec![1, 4, 2, 8, 5, 7])
+        );
+        assert_eq!(expansion(-7, 4, 10), (true, 1, vec![7, 5], vec![]));
+        assert_eq!(expansion(0, 1, 10), (false, 0, vec![], vec![]));
+        assert_eq!(expansion(1, 3, 2), (false, 0, vec![], vec![0, 1]));
+        assert_eq!(expansion(22, 7, 10).3.len(), 6);
+        assert_eq!(expansion(1, 983, 10).3.len(), 982);
+        assert_eq!(expansion(1, 12, 12), (false, 0, vec![1], vec![]));
+        assert_eq!(
+            expansion(1, 14, 10),
+            (false, 0, vec![0], vec![7, 1, 4, 2, 8, 5])
+        );
+    }
+}
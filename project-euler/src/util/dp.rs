@@ -0,0 +1,251 @@
+use std::ops::{Add, Mul, Sub};
+
+use num_traits::{One, Zero};
+
+// ---
+// --- Saturating counter
+// ---
+
+/// Counter that saturates at `MAX`, which is enough to tell if there are
+/// no, one or several ways to reach a value without risking an overflow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Capped<const MAX: u8>(pub u8);
+
+impl<const MAX: u8> Add for Capped<MAX> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(std::cmp::min(self.0.saturating_add(rhs.0), MAX))
+    }
+}
+
+impl<const MAX: u8> Zero for Capped<MAX> {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<const MAX: u8> One for Capped<MAX> {
+    fn one() -> Self {
+        Self(std::cmp::min(1, MAX))
+    }
+}
+
+impl<const MAX: u8> Mul for Capped<MAX> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(std::cmp::min(self.0.saturating_mul(rhs.0), MAX))
+    }
+}
+
+// ---
+// --- Knapsack counting
+// ---
+
+/// Number of ways to reach each sum in `0..=target` with items of given
+/// weights, each of which can be used any number of times.
+pub fn count_unbounded<T>(weights: &[usize], target: usize) -> Vec<T>
+where
+    T: Clone + Zero + One,
+{
+    let mut ways = vec![T::zero(); target + 1];
+    ways[0] = T::one();
+
+    for &weight in weights.iter().filter(|&&weight| weight > 0) {
+        for sum in weight..=target {
+            ways[sum] = ways[sum].clone() + ways[sum - weight].clone();
+        }
+    }
+
+    ways
+}
+
+/// Number of ways to reach each sum in `0..=target` with items given as pairs
+/// of a weight and the number of times it can be used.
+pub fn count_bounded<T>(items: &[(usize, usize)], target: usize) -> Vec<T>
+where
+    T: Clone + Zero + One + Sub<Output = T>,
+{
+    let mut ways = vec![T::zero(); target + 1];
+    ways[0] = T::one();
+
+    for &(weight, count) in items.iter().filter(|(weight, _)| *weight > 0) {
+        let prev = ways.clone();
+
+        // ways[s] is the sum of prev[s - k * weight] for k in 0..=count, which
+        // is a sliding window over sums congruent modulo the weight.
+        for sum in weight..=target {
+            let mut next = ways[sum].clone() + ways[sum - weight].clone();
+
+            if let Some(out) = sum.checked_sub((count + 1) * weight) {
+                next = next - prev[out].clone();
+            }
+
+            ways[sum] = next;
+        }
+    }
+
+    ways
+}
+
+/// Number of ways to reach each sum by picking exactly `k` distinct items of
+/// the list, the result goes up to the sum of all weights.
+pub fn count_k_subsets<T>(weights: &[usize], k: usize) -> Vec<T>
+where
+    T: Clone + Zero + One,
+{
+    let mut sorted = weights.to_vec();
+    sorted.sort_unstable();
+
+    // Sums of `j` items are bounded by sums of the `j` smallest and largest
+    let prefix_sums = |weights: &mut dyn Iterator<Item = &usize>| -> Vec<usize> {
+        std::iter::once(0)
+            .chain(weights.scan(0, |acc, &w| {
+                *acc += w;
+                Some(*acc)
+            }))
+            .collect()
+    };
+
+    let min_sum = prefix_sums(&mut sorted.iter());
+    let max_sum = prefix_sums(&mut sorted.iter().rev());
+
+    if k > sorted.len() {
+        return vec![T::zero(); max_sum[sorted.len()] + 1];
+    }
+
+    // ways[j][s] is the number of subsets of size `j` with sum `s`
+    let mut ways: Vec<Vec<T>> = (0..=k).map(|j| vec![T::zero(); max_sum[j] + 1]).collect();
+    ways[0][0] = T::one();
+
+    for (i, &weight) in sorted.iter().enumerate() {
+        // Items that are left must be enough to get `k` items
+        let min_size = (k + i + 1).saturating_sub(sorted.len()).max(1);
+
+        for j in (min_size..=std::cmp::min(i + 1, k)).rev() {
+            let (head, tail) = ways.split_at_mut(j);
+            let (prev, next) = (&head[j - 1], &mut tail[0]);
+
+            // Sums of `j - 1` items are at least the sum of the smallest ones
+            let lo = min_sum[j - 1];
+
+            for (slot, ways_prev) in next[lo + weight..].iter_mut().zip(&prev[lo..]) {
+                *slot = slot.clone() + ways_prev.clone();
+            }
+        }
+    }
+
+    let mut res = ways.pop().unwrap();
+    res.resize(max_sum[sorted.len()] + 1, T::zero());
+    res
+}
+
+// ---
+// --- Partitions
+// ---
+
+/// Number of partitions p(n) for all n in `0..=max`, computed with Euler's
+/// pentagonal number theorem.
+pub fn partition_counts<T>(max: usize) -> Vec<T>
+where
+    T: Clone + Zero + One + Add<Output = T> + Sub<Output = T>,
+{
+    let mut p = Vec::with_capacity(max + 1);
+    p.push(T::one());
+
+    for n in 1..=max {
+        // Terms are added by pairs of generalized pentagonal numbers, with
+        // alternating signs.
+        let mut positive = T::zero();
+        let mut negative = T::zero();
+
+        for k in 1.. {
+            let pentagonal = k * (3 * k - 1) / 2;
+
+            if pentagonal > n {
+                break;
+            }
+
+            let mut term = p[n - pentagonal].clone();
+
+            if let Some(rest) = n.checked_sub(pentagonal + k) {
+                term = term + p[rest].clone();
+            }
+
+            if k % 2 == 1 {
+                positive = positive + term;
+            } else {
+                negative = negative + term;
+            }
+        }
+
+        p.push(positive - negative);
+    }
+
+    p
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::modular::ModInt;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_count_unbounded() {
+        assert_eq!(count_unbounded::<u64>(&[1, 2, 5], 5), [1, 1, 2, 2, 3, 4]);
+        assert_eq!(count_unbounded::<u64>(&[], 2), [1, 0, 0]);
+
+        let ways = count_unbounded::<BigUint>(&[1, 2, 5, 10, 20, 50, 100, 200], 200);
+        assert_eq!(ways[200], BigUint::from(73682u32));
+    }
+
+    #[test]
+    fn test_count_bounded() {
+        assert_eq!(
+            count_bounded::<u64>(&[(1, 2), (2, 1)], 5),
+            [1, 1, 2, 1, 1, 0]
+        );
+        assert_eq!(count_bounded::<u64>(&[(3, 0)], 3), [1, 0, 0, 0]);
+
+        // Matches the unbounded version when counts are large enough
+        let items: Vec<_> = [1, 3, 4, 7].iter().map(|&w| (w, 100 / w)).collect();
+        assert_eq!(
+            count_bounded::<ModInt<1_000_000_007>>(&items, 100),
+            count_unbounded::<ModInt<1_000_000_007>>(&[1, 3, 4, 7], 100)
+        );
+    }
+
+    #[test]
+    fn test_count_k_subsets() {
+        let ways = count_k_subsets::<u64>(&[1, 3, 6, 8, 10, 11], 3);
+        assert_eq!(ways.len(), 40);
+        assert_eq!(ways[10], 1);
+        assert_eq!(ways[15], 2);
+        assert_eq!(ways.iter().sum::<u64>(), 20);
+
+        let unique: usize = (count_k_subsets::<Capped<2>>(&[1, 3, 6, 8, 10, 11], 3).iter())
+            .enumerate()
+            .filter(|(_, ways)| ways.0 == 1)
+            .map(|(sum, _)| sum)
+            .sum();
+
+        assert_eq!(unique, 156);
+        assert_eq!(count_k_subsets::<u64>(&[1, 2], 0), [1, 0, 0, 0]);
+        assert_eq!(count_k_subsets::<u64>(&[1, 2], 3), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_partition_counts() {
+        let p = partition_counts::<u64>(10);
+        assert_eq!(p, [1, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42]);
+
+        let p = partition_counts::<BigUint>(100);
+        assert_eq!(p[100], BigUint::from(190_569_292u32));
+    }
+}
@@ -0,0 +1,258 @@
+use num_rational::Ratio;
+
+use crate::util::multiplicative::LinearSieve;
+
+// ---
+// --- Farey sequences
+// ---
+
+/// Iterator over reduced fractions of [0, 1] with a denominator of at most
+/// `n`, in increasing order.
+pub struct Farey {
+    n: u64,
+    // Two consecutive terms, the first one is the next to be yielded.
+    curr: Option<(u64, u64)>,
+    next: (u64, u64),
+}
+
+impl Iterator for Farey {
+    type Item = Ratio<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (a, b) = self.curr?;
+        let (c, d) = self.next;
+
+        self.curr = if (a, b) == (1, 1) {
+            None
+        } else {
+            // Fractions following a/b and c/d are (kc - a) / (kd - b), where
+            // k is as large as possible.
+            let k = (self.n + b) / d;
+            self.next = (k * c - a, k * d - b);
+            Some((c, d))
+        };
+
+        Some(Ratio::new_raw(a, b))
+    }
+}
+
+pub fn farey(n: u64) -> Farey {
+    Farey {
+        n,
+        curr: Some((0, 1)).filter(|_| n > 0),
+        next: (1, n),
+    }
+}
+
+/// Number of terms in the Farey sequence of order `n`, which is computed from
+/// a table of Euler's totient function.
+pub fn farey_len(n: u32) -> u64 {
+    let phi = LinearSieve::new(n).euler_phi();
+    1 + phi[1..].iter().map(|&x| u64::from(x)).sum::<u64>()
+}
+
+/// Number of reduced fractions strictly between `lo` and `hi` with a
+/// denominator of at most `max_den`.
+pub fn count_fractions_between(lo: &Ratio<u64>, hi: &Ratio<u64>, max_den: u32) -> u64 {
+    if lo >= hi {
+        return 0;
+    }
+
+    let (a, b) = (u128::from(*lo.numer()), u128::from(*lo.denom()));
+    let (c, d) = (u128::from(*hi.numer()), u128::from(*hi.denom()));
+
+    // all[m] counts fractions p / q in the interval with q <= m, including
+    // fractions that are not reduced, that is a·q/b < p < c·q/d.
+    let all: Vec<i64> = std::iter::once(0)
+        .chain((1..=u128::from(max_den)).scan(0, |acc, q| {
+            *acc += ((c * q - 1) / d).saturating_sub(a * q / b) as i64;
+            Some(*acc)
+        }))
+        .collect();
+
+    // Each fraction p / q is counted once in all[m / g] for g dividing
+    // gcd(p, q), which is reverted by a Möbius inversion.
+    let mobius = LinearSieve::new(max_den).mobius();
+
+    (1..=max_den as usize)
+        .map(|g| i64::from(mobius[g]) * all[max_den as usize / g])
+        .sum::<i64>() as u64
+}
+
+// ---
+// --- Stern-Brocot tree
+// ---
+
+/// Closest fractions strictly bellow and above `x` with a denominator of at
+/// most `max_den`, found by walking down the Stern-Brocot tree. The lower one
+/// doesn't exist if `x` is zero.
+pub fn neighbors(x: &Ratio<u64>, max_den: u64) -> (Option<Ratio<u64>>, Ratio<u64>) {
+    assert!(max_den > 0, "denominators must be positive");
+    let (p, q) = (u128::from(*x.numer()), u128::from(*x.denom()));
+    let max_den = u128::from(max_den);
+
+    // Current bounds a/b < x < c/d, where the right bound starts at infinity
+    let (mut a, mut b) = (0, 1);
+    let (mut c, mut d) = (1, 0);
+
+    if p == 0 {
+        // Fractions are non-negative, the only candidates are 1/k
+        return (None, Ratio::new_raw(1, max_den as u64));
+    }
+
+    loop {
+        let (m, n) = (a + c, b + d);
+
+        if n > max_den {
+            break;
+        }
+
+        if m * q == n * p {
+            // The neighbors of x are its parents in the tree, to which x is
+            // added as many times as possible.
+            let k_left = (max_den - b) / q;
+            let k_right = (max_den - d) / q;
+            (a, b) = (a + k_left * p, b + k_left * q);
+            (c, d) = (c + k_right * p, d + k_right * q);
+            break;
+        }
+
+        if m * q < n * p {
+            // Move the left bound as many times as possible to the right:
+            // (a + kc) / (b + kd) < p / q iff k·(qc - pd) < pb - qa.
+            let k_max = (p * b - q * a - 1) / (q * c - p * d);
+            let k = (max_den - b).checked_div(d).map_or(k_max, |k| k_max.min(k));
+            (a, b) = (a + k * c, b + k * d);
+        } else {
+            let k_max = (q * c - p * d - 1) / (p * b - q * a);
+            let k = k_max.min((max_den - d) / b);
+            (c, d) = (c + k * a, d + k * b);
+        }
+    }
+
+    (
+        Some(Ratio::new_raw(a as u64, b as u64)),
+        Ratio::new_raw(c as u64, d as u64),
+    )
+}
+
+/// Closest fraction to `x` with a denominator of at most `max_den`, the one
+/// with the smallest denominator is picked in case of a tie.
+pub fn best_approximation(x: &Ratio<u64>, max_den: u64) -> Ratio<u64> {
+    if *x.denom() <= max_den {
+        return *x;
+    }
+
+    let (left, right) = neighbors(x, max_den);
+    let left = left.expect("x is not an integer");
+
+    let dist_left = x - left;
+    let dist_right = right - x;
+
+    match dist_left.cmp(&dist_right) {
+        std::cmp::Ordering::Less => left,
+        std::cmp::Ordering::Greater => right,
+        std::cmp::Ordering::Equal if left.denom() <= right.denom() => left,
+        std::cmp::Ordering::Equal => right,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn r(p: u64, q: u64) -> Ratio<u64> {
+        Ratio::new(p, q)
+    }
+
+    #[test]
+    fn test_farey() {
+        let seq: Vec<_> = farey(5).collect();
+
+        assert_eq!(
+            seq,
+            [
+                r(0, 1),
+                r(1, 5),
+                r(1, 4),
+                r(1, 3),
+                r(2, 5),
+                r(1, 2),
+                r(3, 5),
+                r(2, 3),
+                r(3, 4),
+                r(4, 5),
+                r(1, 1)
+            ]
+        );
+
+        assert_eq!(farey(1).count(), 2);
+        assert_eq!(farey(0).count(), 0);
+        assert_eq!(farey(8).count() - 2, 21);
+
+        for n in 1..50 {
+            assert_eq!(farey(n).count() as u64, farey_len(n as u32));
+            assert!(farey(n).zip(farey(n).skip(1)).all(|(x, y)| x < y));
+        }
+
+        assert_eq!(farey_len(1_000_000) - 2, 303_963_552_391);
+    }
+
+    #[test]
+    fn test_count_fractions_between() {
+        assert_eq!(count_fractions_between(&r(1, 3), &r(1, 2), 8), 3);
+        assert_eq!(count_fractions_between(&r(1, 2), &r(1, 3), 8), 0);
+        assert_eq!(
+            count_fractions_between(&r(1, 3), &r(1, 2), 12_000),
+            7_295_372
+        );
+
+        for n in 1..40 {
+            for (lo, hi) in [(r(0, 1), r(1, 1)), (r(1, 7), r(5, 9)), (r(2, 3), r(3, 4))] {
+                let expected = farey(n).filter(|x| lo < *x && *x < hi).count();
+                assert_eq!(count_fractions_between(&lo, &hi, n as u32), expected as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_neighbors() {
+        assert_eq!(neighbors(&r(3, 7), 8), (Some(r(2, 5)), r(1, 2)));
+        assert_eq!(
+            neighbors(&r(3, 7), 1_000_000),
+            (Some(r(428_570, 999_997)), r(428_569, 999_994))
+        );
+
+        assert_eq!(neighbors(&r(0, 1), 5), (None, r(1, 5)));
+        assert_eq!(neighbors(&r(7, 2), 1), (Some(r(3, 1)), r(4, 1)));
+
+        // Compare with the Farey sequence, shifted to cover fractions above 1
+        for n in 1..30 {
+            let seq: Vec<_> = std::iter::once(r(0, 1))
+                .chain((0..4).flat_map(|i| farey(n).skip(1).map(move |x| x + i)))
+                .collect();
+
+            for x in [r(1, 3), r(5, 17), r(22, 7), r(1, 1), r(31, 37)] {
+                let pos = seq.partition_point(|y| *y < x);
+                let left = seq[..pos].last().copied();
+                let right = if seq.get(pos) == Some(&x) {
+                    seq[pos + 1]
+                } else {
+                    seq[pos]
+                };
+
+                assert_eq!(neighbors(&x, n), (left, right));
+            }
+        }
+    }
+
+    #[test]
+    fn test_best_approximation() {
+        let pi = r(314_159_265_358_979, 100_000_000_000_000);
+        assert_eq!(best_approximation(&pi, 10), r(22, 7));
+        assert_eq!(best_approximation(&pi, 1000), r(355, 113));
+        assert_eq!(best_approximation(&r(1, 2), 3), r(1, 2));
+        assert_eq!(best_approximation(&r(1, 4), 3), r(1, 3));
+        assert_eq!(best_approximation(&r(1, 2), 1), r(0, 1));
+    }
+}
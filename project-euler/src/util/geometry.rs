@@ -0,0 +1,342 @@
+use std::cmp::Ordering;
+use std::ops::{Div, Mul, Sub};
+
+use num_integer::Integer;
+use num_traits::Zero;
+
+use crate::util::linalg::Vector2;
+
+// ---
+// --- Orientation
+// ---
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    Collinear,
+    CounterClockwise,
+}
+
+/// Z coordinate of the cross product of two vectors.
+pub fn cross<T>(u: &Vector2<T>, v: &Vector2<T>) -> T
+where
+    T: Clone + Mul<Output = T> + Sub<Output = T>,
+{
+    u.x().clone() * v.y().clone() - u.y().clone() * v.x().clone()
+}
+
+fn diff<T: Clone + Sub<Output = T>>(u: &Vector2<T>, v: &Vector2<T>) -> Vector2<T> {
+    u.clone() - v.clone()
+}
+
+/// Orientation of the turn made by going through `a`, `b` and then `c`.
+pub fn orientation<T>(a: &Vector2<T>, b: &Vector2<T>, c: &Vector2<T>) -> Orientation
+where
+    T: Clone + Zero + Ord + Mul<Output = T> + Sub<Output = T>,
+{
+    match cross(&diff(b, a), &diff(c, a)).cmp(&T::zero()) {
+        Ordering::Less => Orientation::Clockwise,
+        Ordering::Equal => Orientation::Collinear,
+        Ordering::Greater => Orientation::CounterClockwise,
+    }
+}
+
+// ---
+// --- Segments
+// ---
+
+/// Check if `p` belongs to the closed segment [a, b].
+pub fn on_segment<T>(p: &Vector2<T>, (a, b): (&Vector2<T>, &Vector2<T>)) -> bool
+where
+    T: Clone + Zero + Ord + Mul<Output = T> + Sub<Output = T>,
+{
+    let between = |p: &T, a: &T, b: &T| std::cmp::min(a, b) <= p && p <= std::cmp::max(a, b);
+
+    orientation(a, b, p) == Orientation::Collinear
+        && between(p.x(), a.x(), b.x())
+        && between(p.y(), a.y(), b.y())
+}
+
+/// Check if two closed segments have at least one common point.
+pub fn segments_intersect<T>(
+    (a, b): (&Vector2<T>, &Vector2<T>),
+    (c, d): (&Vector2<T>, &Vector2<T>),
+) -> bool
+where
+    T: Clone + Zero + Ord + Mul<Output = T> + Sub<Output = T>,
+{
+    let (o1, o2) = (orientation(a, b, c), orientation(a, b, d));
+    let (o3, o4) = (orientation(c, d, a), orientation(c, d, b));
+
+    (o1 != o2 && o3 != o4 && ![o1, o2, o3, o4].contains(&Orientation::Collinear))
+        || on_segment(c, (a, b))
+        || on_segment(d, (a, b))
+        || on_segment(a, (c, d))
+        || on_segment(b, (c, d))
+}
+
+/// Intersection point of lines (ab) and (cd), which should be over a field
+/// such as `Rational64`. Returns None if the lines are parallel.
+pub fn line_intersection<T>(
+    (a, b): (&Vector2<T>, &Vector2<T>),
+    (c, d): (&Vector2<T>, &Vector2<T>),
+) -> Option<Vector2<T>>
+where
+    T: Clone + Zero + Mul<Output = T> + Sub<Output = T> + Div<Output = T>,
+{
+    let (u, v) = (diff(b, a), diff(d, c));
+    let denominator = cross(&u, &v);
+
+    if denominator.is_zero() {
+        return None;
+    }
+
+    // The intersection is a + t·u
+    let t = cross(&diff(c, a), &v) / denominator;
+
+    Some(Vector2::from([
+        a.x().clone() + t.clone() * u.x().clone(),
+        a.y().clone() + t * u.y().clone(),
+    ]))
+}
+
+// ---
+// --- Polygons
+// ---
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Position of a point relative to a simple polygon given by its vertices.
+pub fn point_in_polygon<T>(p: &Vector2<T>, polygon: &[Vector2<T>]) -> Location
+where
+    T: Clone + Zero + Ord + Mul<Output = T> + Sub<Output = T>,
+{
+    let mut inside = false;
+
+    for (a, b) in polygon.iter().zip(polygon.iter().cycle().skip(1)) {
+        if on_segment(p, (a, b)) {
+            return Location::Boundary;
+        }
+
+        // Count crossings with a horizontal ray going right from `p`, each
+        // edge being considered half-open to count vertices only once.
+        if (a.y() > p.y()) != (b.y() > p.y()) {
+            let (low, high) = if a.y() < b.y() { (a, b) } else { (b, a) };
+
+            if orientation(low, high, p) == Orientation::CounterClockwise {
+                inside = !inside;
+            }
+        }
+    }
+
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Twice the signed area of a polygon given by the shoelace formula, which is
+/// positive if vertices are given counterclockwise.
+pub fn double_signed_area<T>(polygon: &[Vector2<T>]) -> T
+where
+    T: Clone + Zero + Mul<Output = T> + Sub<Output = T>,
+{
+    (polygon.iter().zip(polygon.iter().cycle().skip(1)))
+        .fold(T::zero(), |acc, (a, b)| acc + cross(a, b))
+}
+
+/// Convex hull of a set of points computed with Andrew's monotone chain, given
+/// counterclockwise and without collinear points.
+pub fn convex_hull<T>(points: &[Vector2<T>]) -> Vec<Vector2<T>>
+where
+    T: Clone + Zero + Ord + Mul<Output = T> + Sub<Output = T>,
+{
+    let mut points = points.to_vec();
+    points.sort_by(|u, v| (u.x(), u.y()).cmp(&(v.x(), v.y())));
+    points.dedup_by(|u, v| u.x() == v.x() && u.y() == v.y());
+
+    if points.len() < 3 {
+        return points;
+    }
+
+    // Push a point after removing previous points that don't make a left
+    // turn, leaving at least `min_len` points.
+    let push = |hull: &mut Vec<Vector2<T>>, min_len: usize, p: &Vector2<T>| {
+        while hull.len() >= min_len
+            && orientation(&hull[hull.len() - 2], &hull[hull.len() - 1], p)
+                != Orientation::CounterClockwise
+        {
+            hull.pop();
+        }
+
+        hull.push(p.clone());
+    };
+
+    let mut hull = Vec::with_capacity(2 * points.len());
+
+    for p in &points {
+        push(&mut hull, 2, p);
+    }
+
+    // The upper hull must not pop points from the lower hull
+    let lower_len = hull.len();
+
+    for p in points.iter().rev().skip(1) {
+        push(&mut hull, lower_len + 1, p);
+    }
+
+    // The first point was pushed again at the end of the upper hull
+    hull.pop();
+    hull
+}
+
+// ---
+// --- Lattice polygons
+// ---
+
+/// Number of lattice points on the boundary of a polygon.
+pub fn boundary_points(polygon: &[Vector2<i64>]) -> u64 {
+    (polygon.iter().zip(polygon.iter().cycle().skip(1)))
+        .map(|(a, b)| {
+            let d = *b - *a;
+            d.x().gcd(d.y()).unsigned_abs()
+        })
+        .sum()
+}
+
+/// Number of lattice points strictly inside of a simple polygon with integer
+/// vertices, using Pick's theorem. Degenerate polygons, whose vertices are
+/// all collinear, have no interior point.
+pub fn interior_points(polygon: &[Vector2<i64>]) -> u64 {
+    let double_area = double_signed_area(polygon).unsigned_abs();
+
+    if double_area == 0 {
+        return 0;
+    }
+
+    // A = I + B/2 - 1
+    (double_area + 2 - boundary_points(polygon)) / 2
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num_rational::Rational64;
+
+    fn r(x: i64, y: i64) -> Vector2<Rational64> {
+        v![x.into(), y.into()]
+    }
+
+    #[test]
+    fn test_orientation() {
+        assert_eq!(cross(&v![1, 0], &v![0, 1]), 1);
+        assert_eq!(
+            orientation(&v![0, 0], &v![1, 0], &v![1, 1]),
+            Orientation::CounterClockwise
+        );
+        assert_eq!(
+            orientation(&v![0, 0], &v![1, 0], &v![1, -1]),
+            Orientation::Clockwise
+        );
+        assert_eq!(
+            orientation(&v![0, 0], &v![1, 1], &v![3, 3]),
+            Orientation::Collinear
+        );
+        assert_eq!(
+            orientation(&r(0, 0), &r(1, 0), &r(1, 1)),
+            Orientation::CounterClockwise
+        );
+    }
+
+    #[test]
+    fn test_segments() {
+        let seg = |a: [i64; 2], b: [i64; 2]| (Vector2::from(a), Vector2::from(b));
+        let inter = |(a, b): &(_, _), (c, d): &(_, _)| segments_intersect((a, b), (c, d));
+
+        assert!(inter(&seg([0, 0], [2, 2]), &seg([0, 2], [2, 0])));
+        assert!(inter(&seg([0, 0], [2, 2]), &seg([2, 2], [3, 0])));
+        assert!(inter(&seg([0, 0], [2, 0]), &seg([1, 0], [3, 0])));
+        assert!(!inter(&seg([0, 0], [1, 0]), &seg([2, 0], [3, 0])));
+        assert!(!inter(&seg([0, 0], [1, 1]), &seg([1, 0], [2, -1])));
+        assert!(on_segment(&v![1, 1], (&v![0, 0], &v![2, 2])));
+        assert!(!on_segment(&v![3, 3], (&v![0, 0], &v![2, 2])));
+
+        assert_eq!(
+            line_intersection((&r(0, 0), &r(2, 1)), (&r(0, 1), &r(1, 0))),
+            Some(v![Rational64::new(2, 3), Rational64::new(1, 3)])
+        );
+        assert_eq!(
+            line_intersection((&r(0, 0), &r(1, 1)), (&r(0, 1), &r(1, 2))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        let square = [v![0, 0], v![4, 0], v![4, 4], v![0, 4]];
+        assert_eq!(point_in_polygon(&v![2, 2], &square), Location::Inside);
+        assert_eq!(point_in_polygon(&v![4, 2], &square), Location::Boundary);
+        assert_eq!(point_in_polygon(&v![0, 0], &square), Location::Boundary);
+        assert_eq!(point_in_polygon(&v![5, 0], &square), Location::Outside);
+        assert_eq!(point_in_polygon(&v![-1, 4], &square), Location::Outside);
+
+        // Concave polygon where the ray goes through a vertex
+        let arrow = [v![0, 0], v![4, 2], v![0, 4], v![2, 2]];
+        assert_eq!(point_in_polygon(&v![1, 2], &arrow), Location::Outside);
+        assert_eq!(point_in_polygon(&v![3, 2], &arrow), Location::Inside);
+        assert_eq!(point_in_polygon(&v![2, 2], &arrow), Location::Boundary);
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let points = [
+            v![0, 0],
+            v![2, 2],
+            v![1, 1],
+            v![2, 0],
+            v![0, 2],
+            v![1, 0],
+            v![0, 0],
+            v![1, 3],
+        ];
+
+        let hull = convex_hull(&points);
+        assert_eq!(hull, [v![0, 0], v![2, 0], v![2, 2], v![1, 3], v![0, 2]]);
+        assert_eq!(convex_hull(&[v![1, 1], v![1, 1]]), [v![1, 1]]);
+        assert_eq!(
+            convex_hull(&[v![0, 0], v![1, 1], v![2, 2]]),
+            [v![0, 0], v![2, 2]]
+        );
+    }
+
+    #[test]
+    fn test_area() {
+        let square = [v![0, 0], v![4, 0], v![4, 4], v![0, 4]];
+        assert_eq!(double_signed_area(&square), 32);
+
+        let mut reversed = square;
+        reversed.reverse();
+        assert_eq!(double_signed_area(&reversed), -32);
+
+        let triangle = [r(0, 0), r(3, 0), r(0, 3)];
+        assert_eq!(
+            double_signed_area(&triangle) / Rational64::from(2),
+            Rational64::new(9, 2)
+        );
+
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+        assert_eq!(interior_points(&[v![0, 0], v![5, 0], v![0, 3]]), 4);
+
+        let segment = [v![0, 0], v![3, 3], v![6, 6]];
+        assert_eq!(boundary_points(&segment), 12);
+        assert_eq!(interior_points(&segment), 0);
+        assert_eq!(interior_points(&[v![2, 1]]), 0);
+    }
+}
@@ -1,9 +1,14 @@
-use std::array::IntoIter;
-use std::convert::{From, TryInto};
+use std::convert::From;
 use std::iter::Sum;
-use std::ops::{Add, AddAssign, Deref, DerefMut, Mul, Neg, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Deref, DerefMut, Div, Index, IndexMut, Mul, Neg, Sub, SubAssign};
 
-#[derive(Copy, Clone)]
+use num_traits::{One, Zero};
+
+// ---
+// --- Vector
+// ---
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Vector<T, const N: usize>([T; N]);
 
 pub type Vector2<T> = Vector<T, 2>;
@@ -39,7 +44,7 @@ where
     T::Output: Sum,
 {
     pub fn dot(self, rhs: Self) -> T::Output {
-        (IntoIter::new(self.0).zip(IntoIter::new(rhs.0)))
+        (IntoIterator::into_iter(self.0).zip(IntoIterator::into_iter(rhs.0)))
             .map(|(x, y)| x * y)
             .sum()
     }
@@ -75,13 +80,7 @@ impl<T: Neg, const N: usize> Neg for Vector<T, N> {
     type Output = Vector<T::Output, N>;
 
     fn neg(self) -> Self::Output {
-        Vector(
-            IntoIter::new(self.0)
-                .map(|x| -x)
-                .collect::<Vec<_>>()
-                .try_into()
-                .unwrap_or_else(|_| panic!("dimension inconsistency")),
-        )
+        Vector(self.0.map(|x| -x))
     }
 }
 
@@ -89,19 +88,14 @@ impl<T: Add, const N: usize> Add for Vector<T, N> {
     type Output = Vector<T::Output, N>;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Vector(
-            (IntoIter::new(self.0).zip(IntoIter::new(rhs.0)))
-                .map(|(x, y)| x + y)
-                .collect::<Vec<_>>()
-                .try_into()
-                .unwrap_or_else(|_| panic!("dimension inconsistency")),
-        )
+        let mut rhs = IntoIterator::into_iter(rhs.0);
+        Vector(self.0.map(|x| x + rhs.next().unwrap()))
     }
 }
 
 impl<T: AddAssign, const N: usize> AddAssign for Vector<T, N> {
     fn add_assign(&mut self, rhs: Self) {
-        for (x, y) in self.iter_mut().zip(IntoIter::new(rhs.0)) {
+        for (x, y) in self.iter_mut().zip(IntoIterator::into_iter(rhs.0)) {
             *x += y;
         }
     }
@@ -111,20 +105,324 @@ impl<T: Sub, const N: usize> Sub for Vector<T, N> {
     type Output = Vector<T::Output, N>;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        Vector(
-            (IntoIter::new(self.0).zip(IntoIter::new(rhs.0)))
-                .map(|(x, y)| x - y)
-                .collect::<Vec<_>>()
-                .try_into()
-                .unwrap_or_else(|_| panic!("dimension inconsistency")),
-        )
+        let mut rhs = IntoIterator::into_iter(rhs.0);
+        Vector(self.0.map(|x| x - rhs.next().unwrap()))
     }
 }
 
 impl<T: SubAssign, const N: usize> SubAssign for Vector<T, N> {
     fn sub_assign(&mut self, rhs: Self) {
-        for (x, y) in self.iter_mut().zip(IntoIter::new(rhs.0)) {
+        for (x, y) in self.iter_mut().zip(IntoIterator::into_iter(rhs.0)) {
             *x -= y;
         }
     }
 }
+
+// ---
+// --- Matrix
+// ---
+
+/// Matrix with `R` rows and `C` columns, stored row by row.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Matrix<T, const R: usize, const C: usize>([[T; C]; R]);
+
+pub type SquareMatrix<T, const N: usize> = Matrix<T, N, N>;
+
+impl<T, const R: usize, const C: usize> Matrix<T, R, C> {
+    pub fn from_fn(mut f: impl FnMut(usize, usize) -> T) -> Self {
+        Self(std::array::from_fn(|i| std::array::from_fn(|j| f(i, j))))
+    }
+
+    pub fn rows(&self) -> &[[T; C]; R] {
+        &self.0
+    }
+
+    pub fn into_rows(self) -> [[T; C]; R] {
+        self.0
+    }
+
+    pub fn transpose(&self) -> Matrix<T, C, R>
+    where
+        T: Clone,
+    {
+        Matrix::from_fn(|i, j| self.0[j][i].clone())
+    }
+}
+
+impl<T: Zero, const R: usize, const C: usize> Matrix<T, R, C> {
+    pub fn zero() -> Self {
+        Self::from_fn(|_, _| T::zero())
+    }
+}
+
+impl<T: Zero + One, const N: usize> SquareMatrix<T, N> {
+    pub fn identity() -> Self {
+        Self::from_fn(|i, j| if i == j { T::one() } else { T::zero() })
+    }
+}
+
+impl<T: Clone + Zero + One, const N: usize> SquareMatrix<T, N> {
+    /// Compute self^exp using exponentiation by squaring.
+    pub fn pow(&self, mut exp: u64) -> Self {
+        let mut base = self.clone();
+        let mut res = Self::identity();
+
+        while exp > 0 {
+            if exp % 2 == 1 {
+                res = res * base.clone();
+            }
+
+            base = base.clone() * base;
+            exp /= 2;
+        }
+
+        res
+    }
+}
+
+// Gaussian elimination
+
+impl<T, const N: usize> SquareMatrix<T, N>
+where
+    T: Clone + Zero + One + Neg<Output = T> + Sub<Output = T> + Div<Output = T>,
+{
+    /// Determinant computed with Bareiss' algorithm, which only performs
+    /// exact divisions and thus also works over integers.
+    pub fn determinant(&self) -> T {
+        let mut a = self.0.clone();
+        let mut sign = T::one();
+        let mut prev_pivot = T::one();
+
+        for k in 0..N {
+            match (k..N).find(|&i| !a[i][k].is_zero()) {
+                Some(i) if i != k => {
+                    a.swap(i, k);
+                    sign = -sign;
+                }
+                Some(_) => {}
+                None => return T::zero(),
+            }
+
+            for i in k + 1..N {
+                for j in k + 1..N {
+                    a[i][j] = (a[i][j].clone() * a[k][k].clone()
+                        - a[i][k].clone() * a[k][j].clone())
+                        / prev_pivot.clone();
+                }
+            }
+
+            prev_pivot = a[k][k].clone();
+        }
+
+        sign * prev_pivot
+    }
+
+    /// Inverse of the matrix, which must be over a field such as `Rational64`
+    /// for the result to be exact. Returns None if the matrix is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut left = self.0.clone();
+        let mut right = Self::identity().0;
+        let mut prev_pivot = T::one();
+
+        // Fraction-free Gauss-Jordan elimination, which ends with `left`
+        // being the identity times the last pivot.
+        for k in 0..N {
+            let pivot_row = (k..N).find(|&i| !left[i][k].is_zero())?;
+            left.swap(pivot_row, k);
+            right.swap(pivot_row, k);
+            let pivot = left[k][k].clone();
+            let pivot_rows = [left[k].clone(), right[k].clone()];
+
+            for i in (0..N).filter(|&i| i != k) {
+                let factor = left[i][k].clone();
+
+                for (rows, pivot_row) in
+                    IntoIterator::into_iter([&mut left, &mut right]).zip(&pivot_rows)
+                {
+                    for (x, y) in rows[i].iter_mut().zip(pivot_row) {
+                        *x = (x.clone() * pivot.clone() - factor.clone() * y.clone())
+                            / prev_pivot.clone();
+                    }
+                }
+            }
+
+            prev_pivot = pivot;
+        }
+
+        Some(Matrix(right).map(|x| x / prev_pivot.clone()))
+    }
+}
+
+impl<T, const R: usize, const C: usize> Matrix<T, R, C> {
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Matrix<U, R, C> {
+        Matrix(self.0.map(|row| row.map(&mut f)))
+    }
+}
+
+impl<T, const R: usize, const C: usize> From<[[T; C]; R]> for Matrix<T, R, C> {
+    fn from(rows: [[T; C]; R]) -> Self {
+        Self(rows)
+    }
+}
+
+// Operators implementations
+
+impl<T, const R: usize, const C: usize> Index<(usize, usize)> for Matrix<T, R, C> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        &self.0[i][j]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<(usize, usize)> for Matrix<T, R, C> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        &mut self.0[i][j]
+    }
+}
+
+impl<T: Neg, const R: usize, const C: usize> Neg for Matrix<T, R, C> {
+    type Output = Matrix<T::Output, R, C>;
+
+    fn neg(self) -> Self::Output {
+        self.map(|x| -x)
+    }
+}
+
+impl<T: Add, const R: usize, const C: usize> Add for Matrix<T, R, C> {
+    type Output = Matrix<T::Output, R, C>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut rhs = IntoIterator::into_iter(rhs.0);
+        Matrix(
+            self.0
+                .map(|row| (Vector(row) + Vector(rhs.next().unwrap())).0),
+        )
+    }
+}
+
+impl<T: Sub, const R: usize, const C: usize> Sub for Matrix<T, R, C> {
+    type Output = Matrix<T::Output, R, C>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut rhs = IntoIterator::into_iter(rhs.0);
+        Matrix(
+            self.0
+                .map(|row| (Vector(row) - Vector(rhs.next().unwrap())).0),
+        )
+    }
+}
+
+impl<T, const R: usize, const C: usize, const K: usize> Mul<Matrix<T, C, K>> for Matrix<T, R, C>
+where
+    T: Clone + Zero + Mul<Output = T>,
+{
+    type Output = Matrix<T, R, K>;
+
+    fn mul(self, rhs: Matrix<T, C, K>) -> Self::Output {
+        Matrix::from_fn(|i, k| {
+            (0..C).fold(T::zero(), |acc, j| {
+                acc + self.0[i][j].clone() * rhs.0[j][k].clone()
+            })
+        })
+    }
+}
+
+impl<T, const R: usize, const C: usize> Mul<Vector<T, C>> for Matrix<T, R, C>
+where
+    T: Clone + Zero + Mul<Output = T>,
+{
+    type Output = Vector<T, R>;
+
+    fn mul(self, rhs: Vector<T, C>) -> Self::Output {
+        Vector(std::array::from_fn(|i| {
+            (0..C).fold(T::zero(), |acc, j| {
+                acc + self.0[i][j].clone() * rhs.0[j].clone()
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::modular::ModInt;
+    use num_rational::{BigRational, Rational64};
+
+    #[test]
+    fn test_vector() {
+        let u: Vector3<i64> = v![1, 2, 3];
+        let v: Vector3<i64> = v![4, -5, 6];
+        assert_eq!(u + v, v![5, -3, 9]);
+        assert_eq!(u - v, v![-3, 7, -3]);
+        assert_eq!(-u, v![-1, -2, -3]);
+        assert_eq!(u.dot(v), 12);
+    }
+
+    #[test]
+    fn test_matrix_operators() {
+        let a = Matrix::from([[1, 2, 3], [4, 5, 6]]);
+        let b = Matrix::from([[1, 0], [0, 1], [1, 1]]);
+        assert_eq!(a * b, Matrix::from([[4, 5], [10, 11]]));
+        assert_eq!(a.transpose(), Matrix::from([[1, 4], [2, 5], [3, 6]]));
+        assert_eq!(a + a - a, a);
+        assert_eq!(-a, a.map(|x| -x));
+        assert_eq!(a * v![1, 1, 1], v![6, 15]);
+        assert_eq!(a[(1, 2)], 6);
+        assert_eq!(
+            SquareMatrix::<i32, 2>::identity(),
+            Matrix::from([[1, 0], [0, 1]])
+        );
+    }
+
+    #[test]
+    fn test_pow() {
+        type F = ModInt<1_000_000_007>;
+        let fib = Matrix::from([[1u64, 1], [1, 0]]).map(F::from);
+        assert_eq!(fib.pow(0), SquareMatrix::identity());
+        assert_eq!(fib.pow(10)[(0, 1)], F::from(55u64));
+        assert_eq!(fib.pow(1000)[(0, 1)], F::from(517_691_607u64));
+    }
+
+    #[test]
+    fn test_determinant() {
+        let a = Matrix::from([[2i64, 0, 1], [1, 3, 2], [1, 1, 2]]);
+        assert_eq!(a.determinant(), 6);
+
+        let b = Matrix::from([[0i64, 1, 2], [3, 4, 5], [6, 7, 8]]);
+        assert_eq!(b.determinant(), 0);
+
+        let c = Matrix::from([[0i64, 2], [3, 4]]);
+        assert_eq!(c.determinant(), -6);
+
+        let d = Matrix::from([[1i64, 2, 3, 4], [5, 6, 7, 8], [2, 6, 4, 8], [3, 1, 1, 2]]);
+        assert_eq!(d.determinant(), 72);
+
+        let e = Matrix::from([[0i64, 3, 1, 4], [5, 0, 9, 2], [6, 5, 0, 5], [8, 9, 7, 0]]);
+        assert_eq!(e.determinant(), -2208);
+
+        let vandermonde = SquareMatrix::<BigRational, 5>::from_fn(|i, j| {
+            BigRational::from_integer((i as u32 + 2).pow(j as u32).into())
+        });
+
+        // Product of (x_j - x_i) for i < j over 2, 3, 4, 5, 6
+        let expected = BigRational::from_integer(288.into());
+        assert_eq!(vandermonde.determinant(), expected);
+        assert_eq!(SquareMatrix::<i64, 0>::identity().determinant(), 1);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let a = Matrix::from([[2i64, 0, 1], [1, 3, 2], [1, 1, 2]]).map(Rational64::from);
+        let inv = a.inverse().unwrap();
+        assert_eq!(a * inv, SquareMatrix::identity());
+        assert_eq!(inv * a, SquareMatrix::identity());
+
+        let b = Matrix::from([[1i64, 2], [2, 4]]).map(Rational64::from);
+        assert_eq!(b.inverse(), None);
+
+        let c = Matrix::from([[0i64, 2], [3, 4]]).map(Rational64::from);
+        let expected = Matrix::from([[(-2, 3), (1, 3)], [(1, 2), (0, 1)]]);
+        assert_eq!(c.inverse(), Some(expected.map(Rational64::from)));
+    }
+}
@@ -1,7 +1,19 @@
+// Declared first so that its macros are available in other modules
+#[macro_use]
+pub mod linalg;
+
 pub mod algorithms;
 pub mod arithmetic;
+pub mod continued_fractions;
+pub mod data;
+pub mod decimal;
+pub mod dp;
+pub mod fractions;
+pub mod geometry;
+pub mod modular;
+pub mod multiplicative;
+pub mod ntt;
+pub mod polynomial;
 pub mod primes;
 pub mod sequences;
-
-#[macro_use]
-pub mod linalg;
+pub mod text;
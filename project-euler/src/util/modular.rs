@@ -0,0 +1,534 @@
+use std::fmt;
+use std::hash::Hash;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use fxhash::FxHashMap;
+use num_bigint::BigUint;
+use num_integer::{Integer, Roots};
+use num_traits::{One, Signed, Unsigned, Zero};
+
+// ---
+// --- MulMod
+// ---
+
+/// Multiplication modulo `m` that can't overflow, both operands are expected
+/// to be lower than `m`.
+pub trait MulMod {
+    fn mul_mod(&self, rhs: &Self, m: &Self) -> Self;
+}
+
+macro_rules! impl_mul_mod_for_primitives {
+    ( $type: ty ) => {
+        impl MulMod for $type {
+            fn mul_mod(&self, rhs: &Self, m: &Self) -> Self {
+                (*self as u128 * *rhs as u128 % *m as u128) as $type
+            }
+        }
+    };
+    ( $( $type: ty ),* ) => {
+        $( impl_mul_mod_for_primitives!($type); )*
+    };
+}
+
+impl_mul_mod_for_primitives!(u8, u16, u32, u64, usize);
+
+impl MulMod for u128 {
+    fn mul_mod(&self, rhs: &Self, m: &Self) -> Self {
+        let (mut a, mut b, mut res) = (self % m, rhs % m, 0);
+
+        if *m <= u128::from(u64::MAX) {
+            return a * b % m;
+        }
+
+        // The product may not fit into 128 bits, fallback to double-and-add.
+        while b > 0 {
+            if b & 1 == 1 {
+                res = add_mod(&res, &a, m);
+            }
+
+            a = add_mod(&a, &a, m);
+            b >>= 1;
+        }
+
+        res
+    }
+}
+
+impl MulMod for BigUint {
+    fn mul_mod(&self, rhs: &Self, m: &Self) -> Self {
+        self * rhs % m
+    }
+}
+
+// ---
+// --- Basic operations
+// ---
+
+pub fn add_mod<T: Clone + Integer>(a: &T, b: &T, m: &T) -> T {
+    let gap = m.clone() - b.clone();
+
+    if *a >= gap {
+        a.clone() - gap
+    } else {
+        a.clone() + b.clone()
+    }
+}
+
+pub fn sub_mod<T: Clone + Integer>(a: &T, b: &T, m: &T) -> T {
+    if a >= b {
+        a.clone() - b.clone()
+    } else {
+        m.clone() - (b.clone() - a.clone())
+    }
+}
+
+pub fn pow_mod<T: Clone + Integer + MulMod>(base: &T, exp: &T, m: &T) -> T {
+    let two = T::one() + T::one();
+    let mut base = base.mod_floor(m);
+    let mut exp = exp.clone();
+    let mut res = T::one().mod_floor(m);
+
+    while !exp.is_zero() {
+        if exp.is_odd() {
+            res = res.mul_mod(&base, m);
+        }
+
+        base = base.mul_mod(&base, m);
+        exp = exp / two.clone();
+    }
+
+    res
+}
+
+/// Return (g, x, y) such that g = gcd(a, b) = ax + by.
+pub fn extended_gcd<T: Clone + Integer + Signed>(a: &T, b: &T) -> (T, T, T) {
+    let (mut r0, mut r1) = (a.clone(), b.clone());
+    let (mut x0, mut x1) = (T::one(), T::zero());
+    let (mut y0, mut y1) = (T::zero(), T::one());
+
+    while !r1.is_zero() {
+        let q = r0.div_floor(&r1);
+        let r2 = r0 - q.clone() * r1.clone();
+        let x2 = x0 - q.clone() * x1.clone();
+        let y2 = y0 - q * y1.clone();
+        r0 = std::mem::replace(&mut r1, r2);
+        x0 = std::mem::replace(&mut x1, x2);
+        y0 = std::mem::replace(&mut y1, y2);
+    }
+
+    if r0.is_negative() {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/// Inverse of `a` modulo `m`, if it is invertible.
+pub fn mod_inverse<T: Clone + Integer + Unsigned + MulMod>(a: &T, m: &T) -> Option<T> {
+    // Extended euclidean algorithm, where only the coefficient of `a` is kept
+    // track of, modulo `m`.
+    let (mut r0, mut r1) = (m.clone(), a.mod_floor(m));
+    let (mut s0, mut s1) = (T::zero(), T::one().mod_floor(m));
+
+    while !r1.is_zero() {
+        let (q, r2) = r0.div_rem(&r1);
+        let s2 = sub_mod(&s0, &q.mod_floor(m).mul_mod(&s1, m), m);
+        r0 = std::mem::replace(&mut r1, r2);
+        s0 = std::mem::replace(&mut s1, s2);
+    }
+
+    Some(s0).filter(|_| r0.is_one())
+}
+
+// ---
+// --- Chinese remainder theorem
+// ---
+
+/// Solve a system of congruences x = a_i mod m_i, where moduli don't need to
+/// be coprime. Return the solution as (x, lcm(m_i)) with x < lcm(m_i), or None
+/// if the system is inconsistent.
+pub fn crt<T: Clone + Integer + Unsigned + MulMod>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    congruences
+        .iter()
+        .try_fold((T::zero(), T::one()), |(a1, m1), (a2, m2)| {
+            let a2 = a2.mod_floor(m2);
+            let g = m1.gcd(m2);
+            let diff = sub_mod(&a2, &a1.mod_floor(m2), m2);
+
+            if !diff.is_multiple_of(&g) {
+                return None;
+            }
+
+            // Find k such that a1 + k * m1 = a2 mod m2
+            let m2_g = m2.clone() / g.clone();
+            let inv = mod_inverse(&(m1.clone() / g.clone()).mod_floor(&m2_g), &m2_g)?;
+            let k = (diff / g.clone()).mod_floor(&m2_g).mul_mod(&inv, &m2_g);
+            let lcm = m1.clone() * m2_g;
+            let x = (a1 + k * m1).mod_floor(&lcm);
+            Some((x, lcm))
+        })
+}
+
+// ---
+// --- Discrete logarithm
+// ---
+
+/// Find the smallest x such that base^x = target mod m using baby-step
+/// giant-step, `base` must be coprime with `m`.
+pub fn discrete_log<T>(base: &T, target: &T, m: &T) -> Option<T>
+where
+    T: Clone + Hash + Integer + Unsigned + MulMod + Roots,
+{
+    let base = base.mod_floor(m);
+    let target = target.mod_floor(m);
+    let n = m.sqrt() + T::one();
+
+    // Baby steps: base^j for 0 <= j < n
+    let mut baby_steps = FxHashMap::default();
+    let (mut j, mut power) = (T::zero(), T::one().mod_floor(m));
+
+    while j < n {
+        baby_steps.entry(power.clone()).or_insert_with(|| j.clone());
+        power = power.mul_mod(&base, m);
+        j = j + T::one();
+    }
+
+    // Giant steps: target * base^(-n * i) for 0 <= i < n
+    let giant_step = pow_mod(&mod_inverse(&base, m)?, &n, m);
+    let (mut i, mut gamma) = (T::zero(), target);
+
+    while i < n {
+        if let Some(j) = baby_steps.get(&gamma) {
+            return Some(i * n.clone() + j.clone());
+        }
+
+        gamma = gamma.mul_mod(&giant_step, m);
+        i = i + T::one();
+    }
+
+    None
+}
+
+// ---
+// --- Modular square root
+// ---
+
+/// Find the smallest x such that x² = a mod p using Tonelli-Shanks algorithm,
+/// where `p` must be a prime. The other root is given by p - x.
+pub fn sqrt_mod<T: Clone + Integer + Unsigned + MulMod>(a: &T, p: &T) -> Option<T> {
+    let one = T::one();
+    let two = T::one() + T::one();
+    let a = a.mod_floor(p);
+
+    if a.is_zero() || *p == two {
+        return Some(a);
+    }
+
+    let p_minus_one = p.clone() - one.clone();
+    let half = p_minus_one.clone() / two.clone();
+
+    if pow_mod(&a, &half, p) != one {
+        return None;
+    }
+
+    // Write p - 1 = q * 2^s with q odd
+    let (mut q, mut s) = (p_minus_one.clone(), 0u32);
+
+    while q.is_even() {
+        q = q / two.clone();
+        s += 1;
+    }
+
+    // Find a quadratic non-residue z
+    let mut z = two.clone();
+
+    while pow_mod(&z, &half, p) != p_minus_one {
+        z = z + one.clone();
+    }
+
+    let mut c = pow_mod(&z, &q, p);
+    let mut t = pow_mod(&a, &q, p);
+    let mut r = pow_mod(&a, &((q + one.clone()) / two), p);
+    let mut m = s;
+
+    while t != one {
+        // Find the least i such that t^(2^i) = 1
+        let mut i = 0;
+        let mut t_pow = t.clone();
+
+        while t_pow != one {
+            t_pow = t_pow.mul_mod(&t_pow, p);
+            i += 1;
+        }
+
+        let mut b = c;
+
+        for _ in 0..m - i - 1 {
+            b = b.mul_mod(&b, p);
+        }
+
+        m = i;
+        c = b.mul_mod(&b, p);
+        t = t.mul_mod(&c, p);
+        r = r.mul_mod(&b, p);
+    }
+
+    let other = p.clone() - r.clone();
+    Some(std::cmp::min(r, other))
+}
+
+// ---
+// --- ModInt
+// ---
+
+/// Integer modulo a prime `M`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub fn new(x: u64) -> Self {
+        Self(x % M)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(self, exp: u64) -> Self {
+        Self(pow_mod(&self.0, &exp, &M))
+    }
+
+    pub fn inverse(self) -> Option<Self> {
+        mod_inverse(&self.0, &M).map(Self)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(x: u64) -> Self {
+        Self::new(x)
+    }
+}
+
+impl<const M: u64> From<i64> for ModInt<M> {
+    fn from(x: i64) -> Self {
+        Self(x.rem_euclid(M as i64) as u64)
+    }
+}
+
+impl<const M: u64> fmt::Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const M: u64> Zero for ModInt<M> {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<const M: u64> One for ModInt<M> {
+    fn one() -> Self {
+        Self(1 % M)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(add_mod(&self.0, &rhs.0, &M))
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(sub_mod(&self.0, &rhs.0, &M))
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // Avoid 128 bits arithmetic when the product fits into a u64, which
+        // is resolved at compile time.
+        if M <= 1 << 32 {
+            Self(self.0 * rhs.0 % M)
+        } else {
+            Self(self.0.mul_mod(&rhs.0, &M))
+        }
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inverse().expect("division by a non-invertible element")
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(sub_mod(&0, &self.0, &M))
+    }
+}
+
+macro_rules! impl_assign_ops_for_mod_int {
+    ( $( $trait: ident :: $method: ident => $op: tt ),* ) => {
+        $(
+            impl<const M: u64> $trait for ModInt<M> {
+                fn $method(&mut self, rhs: Self) {
+                    *self = *self $op rhs;
+                }
+            }
+        )*
+    };
+}
+
+impl_assign_ops_for_mod_int!(
+    AddAssign::add_assign => +,
+    SubAssign::sub_assign => -,
+    MulAssign::mul_assign => *,
+    DivAssign::div_assign => /
+);
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pow_mod() {
+        assert_eq!(pow_mod(&2u64, &10, &1000), 24);
+        assert_eq!(pow_mod(&3u64, &0, &1), 0);
+        assert_eq!(pow_mod(&2u128, &127, &u128::MAX), 1 << 127);
+
+        assert_eq!(pow_mod(&(u128::MAX - 1), &2, &u128::MAX), 1);
+
+        assert_eq!(
+            pow_mod(
+                &BigUint::from(3u8),
+                &BigUint::from(1000u32),
+                &BigUint::from(1_000_000_007u32)
+            ),
+            BigUint::from(pow_mod(&3u64, &1000, &1_000_000_007))
+        );
+    }
+
+    #[test]
+    fn test_mul_mod_u128() {
+        let check = |a: u128, b: u128, m: u128| {
+            let expected = BigUint::from(a) * BigUint::from(b) % BigUint::from(m);
+            assert_eq!(BigUint::from(a.mul_mod(&b, &m)), expected);
+        };
+
+        // Operands are not reduced modulo m
+        check(u128::MAX, u128::MAX - 1, (1 << 100) + 7);
+        check(u128::MAX, 3, (1 << 127) - 1);
+        check(u128::MAX, u128::MAX, 1_000_000_007);
+        check(1 << 70, 5, u128::from(u64::MAX));
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        assert_eq!(extended_gcd(&240i64, &46), (2, -9, 47));
+        assert_eq!(extended_gcd(&-12i64, &18), (6, 1, 1));
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(&3u64, &11), Some(4));
+        assert_eq!(mod_inverse(&6u64, &9), None);
+        assert_eq!(mod_inverse(&5u64, &1), Some(0));
+
+        let m = (1u128 << 127) - 1;
+        let inv = mod_inverse(&12345, &m).unwrap();
+        assert_eq!(inv.mul_mod(&12345, &m), 1);
+
+        assert_eq!(
+            mod_inverse(&BigUint::from(3u8), &BigUint::from(11u8)),
+            Some(BigUint::from(4u8))
+        );
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2u64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(3u64, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt(&[(3u64, 4), (4, 6)]), None);
+        assert_eq!(crt::<u64>(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(&2u64, &1, &11), Some(0));
+        assert_eq!(discrete_log(&2u64, &9, &11), Some(6));
+        assert_eq!(discrete_log(&3u64, &13, &17), Some(4));
+        assert_eq!(discrete_log(&4u64, &2, &7), Some(2));
+        assert_eq!(discrete_log(&2u64, &3, &7), None);
+
+        let p = 1_000_000_007u64;
+        let x = discrete_log(&5, &123_456_789, &p).unwrap();
+        assert_eq!(pow_mod(&5, &x, &p), 123_456_789);
+    }
+
+    #[test]
+    fn test_sqrt_mod() {
+        assert_eq!(sqrt_mod(&10u64, &13), Some(6));
+        assert_eq!(sqrt_mod(&5u64, &13), None);
+        assert_eq!(sqrt_mod(&0u64, &13), Some(0));
+        assert_eq!(sqrt_mod(&1u64, &2), Some(1));
+
+        for p in [17u64, 97, 113, 1_000_000_007] {
+            for a in 0..200 {
+                if let Some(x) = sqrt_mod(&a, &p) {
+                    assert_eq!(x.mul_mod(&x, &p), a % p);
+                } else {
+                    assert_eq!(pow_mod(&a, &((p - 1) / 2), &p), p - 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mod_int() {
+        type F = ModInt<13>;
+        assert_eq!(F::new(7) + F::new(8), F::new(2));
+        assert_eq!(F::new(7) - F::new(8), F::new(12));
+        assert_eq!(F::new(7) * F::new(8), F::new(4));
+        assert_eq!(F::new(7) / F::new(8), F::new(9));
+        assert_eq!(-F::new(7), F::new(6));
+        assert_eq!(F::from(-1i64), F::new(12));
+        assert_eq!(F::new(2).pow(12), F::one());
+        assert_eq!((1..13).map(F::new).product::<F>(), F::new(12));
+    }
+}
@@ -0,0 +1,360 @@
+use std::ops::Mul;
+
+use num_integer::Roots;
+use num_traits::{One, Zero};
+
+use crate::util::arithmetic::floor_blocks;
+use crate::util::primes::factorize;
+
+// ---
+// --- Divisors from factorization
+// ---
+
+/// Enumerate all divisors of a number given its prime factorization, in
+/// increasing order.
+pub fn divisors_from_factors<T>(factors: &[(T, u32)]) -> Vec<T>
+where
+    T: Clone + Ord + One + for<'a> Mul<&'a T, Output = T>,
+{
+    let capacity = factors.iter().map(|(_, exp)| *exp as usize + 1).product();
+    let mut divisors = Vec::with_capacity(capacity);
+    divisors.push(T::one());
+
+    for (p, exp) in factors {
+        let prev_len = divisors.len();
+
+        for i in 0..(*exp as usize) * prev_len {
+            let next = divisors[i].clone() * p;
+            divisors.push(next);
+        }
+    }
+
+    divisors.sort_unstable();
+    divisors
+}
+
+// ---
+// --- Multiplicative functions
+// ---
+
+/// Evaluate a multiplicative function from its value over prime powers.
+pub fn multiplicative<T: One>(n: u64, f: impl Fn(u64, u32) -> T) -> T {
+    factorize(n)
+        .into_iter()
+        .fold(T::one(), |acc, (p, exp)| acc * f(p, exp))
+}
+
+pub fn nb_divisors(n: u64) -> u64 {
+    multiplicative(n, |_, exp| u64::from(exp) + 1)
+}
+
+pub fn sigma(n: u64, k: u32) -> u64 {
+    multiplicative(n, |p, exp| sigma_prime_power(p, exp, k))
+}
+
+pub fn euler_phi(n: u64) -> u64 {
+    multiplicative(n, |p, exp| (p - 1) * p.pow(exp - 1))
+}
+
+pub fn mobius(n: u64) -> i8 {
+    multiplicative(n, |_, exp| if exp == 1 { -1 } else { 0 })
+}
+
+// Sum of the k-th powers of divisors of p^exp.
+fn sigma_prime_power(p: u64, exp: u32, k: u32) -> u64 {
+    let pk = p.pow(k);
+    (0..exp).fold(1, |acc, _| acc * pk + 1)
+}
+
+// ---
+// --- Linear sieve
+// ---
+
+/// Sieve of all integers up to a bound, computed in linear time, that allows
+/// to factorize any number in the range in O(log n) and to build the table of
+/// any multiplicative function in O(n).
+pub struct LinearSieve {
+    primes: Vec<u32>,
+    // Smallest prime factor of each integer.
+    spf: Vec<u32>,
+    // Each integer divided by the largest power of its smallest prime factor.
+    cofactor: Vec<u32>,
+}
+
+impl LinearSieve {
+    pub fn new(max: u32) -> Self {
+        let size = max as usize + 1;
+        let mut primes = Vec::new();
+        let mut spf = vec![0; size];
+        let mut cofactor = vec![1; size];
+
+        for i in 2..size {
+            if spf[i] == 0 {
+                spf[i] = i as u32;
+                primes.push(i as u32);
+            }
+
+            for &p in &primes {
+                let multiple = i * p as usize;
+
+                if p > spf[i] || multiple >= size {
+                    break;
+                }
+
+                spf[multiple] = p;
+
+                cofactor[multiple] = if p == spf[i] { cofactor[i] } else { i as u32 };
+            }
+        }
+
+        Self {
+            primes,
+            spf,
+            cofactor,
+        }
+    }
+
+    pub fn max(&self) -> u32 {
+        (self.spf.len() - 1) as u32
+    }
+
+    pub fn primes(&self) -> &[u32] {
+        &self.primes
+    }
+
+    pub fn is_prime(&self, n: u32) -> bool {
+        n >= 2 && self.spf[n as usize] == n
+    }
+
+    pub fn smallest_prime_factor(&self, n: u32) -> u32 {
+        self.spf[n as usize]
+    }
+
+    pub fn factorize(&self, mut n: u32) -> Vec<(u32, u32)> {
+        let mut factors = Vec::new();
+
+        while n > 1 {
+            let p = self.spf[n as usize];
+            let cofactor = self.cofactor[n as usize];
+            factors.push((p, exponent(n / cofactor, p)));
+            n = cofactor;
+        }
+
+        factors
+    }
+
+    pub fn divisors(&self, n: u32) -> Vec<u32> {
+        divisors_from_factors(&self.factorize(n))
+    }
+
+    /// Table of a multiplicative function over `0..=max`, defined by its value
+    /// over prime powers. The value for 0 is set to zero.
+    pub fn table<T>(&self, f: impl Fn(u32, u32) -> T) -> Vec<T>
+    where
+        T: Clone + Mul<Output = T> + One + Zero,
+    {
+        let mut res = vec![T::zero(); self.spf.len()];
+
+        for n in 1..self.spf.len() {
+            let cofactor = self.cofactor[n] as usize;
+
+            res[n] = if n == 1 {
+                T::one()
+            } else if cofactor == 1 {
+                let p = self.spf[n];
+                f(p, exponent(n as u32, p))
+            } else {
+                res[cofactor].clone() * res[n / cofactor].clone()
+            };
+        }
+
+        res
+    }
+
+    pub fn nb_divisors(&self) -> Vec<u32> {
+        self.table(|_, exp| exp + 1)
+    }
+
+    pub fn sigma(&self, k: u32) -> Vec<u64> {
+        self.table(|p, exp| sigma_prime_power(p.into(), exp, k))
+    }
+
+    pub fn euler_phi(&self) -> Vec<u32> {
+        self.table(|p, exp| (p - 1) * p.pow(exp - 1))
+    }
+
+    pub fn mobius(&self) -> Vec<i8> {
+        self.table(|_, exp| if exp == 1 { -1 } else { 0 })
+    }
+}
+
+// Exponent of `p` in the prime power `pk`.
+fn exponent(mut pk: u32, p: u32) -> u32 {
+    let mut exp = 0;
+
+    while pk > 1 {
+        pk /= p;
+        exp += 1;
+    }
+
+    exp
+}
+
+// ---
+// --- Summatory functions
+// ---
+
+// Bound of tables used by Du's sieve, which is ideally n^(2/3) but is capped
+// to keep the memory usage reasonable.
+const MAX_DU_TABLE: u64 = 1 << 22;
+
+/// Sum of d(k) for k <= n, computed in O(√n) with Dirichlet's hyperbola
+/// method.
+pub fn divisor_summatory(n: u64) -> u128 {
+    let r = n.sqrt();
+    2 * (1..=r).map(|i| u128::from(n / i)).sum::<u128>() - u128::from(r * r)
+}
+
+/// Sum of σ(k) for k <= n, where each i contributes once for each of its n / i
+/// multiples.
+pub fn sigma_summatory(n: u64) -> u128 {
+    (floor_blocks(n))
+        .map(|(block, quotient)| {
+            let (lo, hi) = (u128::from(*block.start()), u128::from(*block.end()));
+            u128::from(quotient) * (lo + hi) * (hi - lo + 1) / 2
+        })
+        .sum()
+}
+
+/// Summatory function F(n) of a function f such that f * 1 has a known
+/// summatory function, which gives the sum of F(n / i) for i in `1..=n`.
+/// Values of F up to `prefix.len() - 1` are read from the table.
+fn du_sieve(n: u64, prefix: &[i64], total: impl Fn(u64) -> i128) -> i128 {
+    let limit = prefix.len() as u64 - 1;
+
+    if n <= limit {
+        return prefix[n as usize].into();
+    }
+
+    // large[k] = F(n / k) for n / k > limit, computed by increasing n / k
+    let max_k = n / (limit + 1);
+    let mut large = vec![0; max_k as usize + 1];
+
+    for k in (1..=max_k).rev() {
+        let v = n / k;
+
+        // The first block is i = 1, for which v / i = v
+        large[k as usize] = (floor_blocks(v).skip(1)).fold(total(v), |acc, (block, q)| {
+            let f_q = if q <= limit {
+                prefix[q as usize].into()
+            } else {
+                large[(k * block.start()) as usize]
+            };
+
+            acc - i128::from(block.end() - block.start() + 1) * f_q
+        });
+    }
+
+    large[1]
+}
+
+fn du_sieve_table<T: Copy + Into<i64>>(n: u64, table: impl Fn(&LinearSieve) -> Vec<T>) -> Vec<i64> {
+    let limit = (n.cbrt() * n.cbrt()).clamp(1, MAX_DU_TABLE);
+    let values = table(&LinearSieve::new(limit as u32));
+
+    (values.iter())
+        .scan(0, |acc, &x| {
+            *acc += x.into();
+            Some(*acc)
+        })
+        .collect()
+}
+
+/// Mertens function, which is the sum of μ(k) for k <= n, computed with Du's
+/// sieve.
+pub fn mertens(n: u64) -> i64 {
+    let prefix = du_sieve_table(n, LinearSieve::mobius);
+    du_sieve(n, &prefix, |_| 1) as i64
+}
+
+/// Sum of φ(k) for k <= n, computed with Du's sieve.
+pub fn totient_summatory(n: u64) -> u128 {
+    let prefix = du_sieve_table(n, LinearSieve::euler_phi);
+    let total = |x: u64| i128::from(x) * (i128::from(x) + 1) / 2;
+    du_sieve(n, &prefix, total) as u128
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_divisors_from_factors() {
+        assert_eq!(divisors_from_factors::<u64>(&[]), [1]);
+        assert_eq!(
+            divisors_from_factors(&[(2u64, 2), (3, 1)]),
+            [1, 2, 3, 4, 6, 12]
+        );
+    }
+
+    #[test]
+    fn test_multiplicative() {
+        assert_eq!(nb_divisors(1), 1);
+        assert_eq!(nb_divisors(28), 6);
+        assert_eq!(sigma(28, 1), 56);
+        assert_eq!(sigma(12, 2), 210);
+        assert_eq!(euler_phi(1), 1);
+        assert_eq!(euler_phi(36), 12);
+        assert_eq!(mobius(30), -1);
+        assert_eq!(mobius(12), 0);
+        assert_eq!(mobius(35), 1);
+    }
+
+    #[test]
+    fn test_summatory() {
+        let (mut d, mut s, mut m, mut p) = (0, 0, 0, 0);
+
+        for n in 1..=2000 {
+            d += u128::from(nb_divisors(n));
+            s += u128::from(sigma(n, 1));
+            m += i64::from(mobius(n));
+            p += u128::from(euler_phi(n));
+            assert_eq!(divisor_summatory(n), d);
+            assert_eq!(sigma_summatory(n), s);
+            assert_eq!(mertens(n), m);
+            assert_eq!(totient_summatory(n), p);
+        }
+
+        assert_eq!(divisor_summatory(0), 0);
+        assert_eq!(mertens(0), 0);
+        assert_eq!(divisor_summatory(1_000_000_000), 20_877_697_634);
+        assert_eq!(mertens(1_000_000_000), -222);
+        assert_eq!(totient_summatory(1_000_000), 303_963_552_392);
+
+        // Above the capped size of the table
+        assert_eq!(mertens(10_000_000_000), -33_722);
+    }
+
+    #[test]
+    fn test_linear_sieve() {
+        let sieve = LinearSieve::new(1000);
+        assert_eq!(sieve.primes().len(), 168);
+        assert_eq!(sieve.factorize(1), []);
+        assert_eq!(sieve.factorize(720), [(2, 4), (3, 2), (5, 1)]);
+        assert_eq!(sieve.divisors(28), [1, 2, 4, 7, 14, 28]);
+
+        let nb_divisors_table = sieve.nb_divisors();
+        let sigma_table = sieve.sigma(1);
+        let sigma_2_table = sieve.sigma(2);
+        let phi_table = sieve.euler_phi();
+        let mobius_table = sieve.mobius();
+
+        for n in 1..=1000 {
+            assert_eq!(u64::from(nb_divisors_table[n as usize]), nb_divisors(n));
+            assert_eq!(sigma_table[n as usize], sigma(n, 1));
+            assert_eq!(sigma_2_table[n as usize], sigma(n, 2));
+            assert_eq!(u64::from(phi_table[n as usize]), euler_phi(n));
+            assert_eq!(mobius_table[n as usize], mobius(n));
+        }
+    }
+}
@@ -0,0 +1,447 @@
+use num_traits::{One, Zero};
+
+use crate::util::modular::ModInt;
+
+/// Prime modulus of the form 119·2^23 + 1, which supports transforms of up to
+/// 2^23 values.
+pub const NTT_PRIME: u64 = 998_244_353;
+
+/// Other primes with 3 as primitive root, used to recover exact products.
+const CRT_PRIMES: (u64, u64, u64) = (NTT_PRIME, 167_772_161, 469_762_049);
+
+// Primitive root of all primes supported by the transform.
+const PRIMITIVE_ROOT: u64 = 3;
+
+// Below this size, convolutions are computed naively.
+const NAIVE_THRESHOLD: usize = 32;
+
+// ---
+// --- Number-theoretic transform
+// ---
+
+/// In-place transform of values whose length must be a power of two dividing
+/// M - 1, where M is a prime that admits 3 as primitive root such as
+/// `NTT_PRIME`.
+pub fn ntt<const M: u64>(values: &mut [ModInt<M>], invert: bool) {
+    let n = values.len();
+
+    assert!(
+        n.is_power_of_two() && (M - 1).is_multiple_of(n as u64),
+        "unsupported transform length"
+    );
+
+    // Bit-reversal permutation
+    let mut j = 0;
+
+    for i in 1..n {
+        let mut bit = n >> 1;
+
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+
+        j ^= bit;
+
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+
+    while len <= n {
+        let mut root = ModInt::<M>::new(PRIMITIVE_ROOT).pow((M - 1) / len as u64);
+
+        if invert {
+            root = root.inverse().unwrap();
+        }
+
+        let powers: Vec<_> = std::iter::successors(Some(ModInt::one()), |&w| Some(w * root))
+            .take(len / 2)
+            .collect();
+
+        for chunk in values.chunks_exact_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+
+            for ((x, y), &w) in lo.iter_mut().zip(hi).zip(&powers) {
+                let t = *y * w;
+                *y = *x - t;
+                *x += t;
+            }
+        }
+
+        len <<= 1;
+    }
+
+    if invert {
+        let inv_n = ModInt::new(n as u64).inverse().unwrap();
+
+        for x in values {
+            *x *= inv_n;
+        }
+    }
+}
+
+/// Product of two polynomials given by their coefficients modulo a prime
+/// supported by `ntt`.
+pub fn convolve<const M: u64>(a: &[ModInt<M>], b: &[ModInt<M>]) -> Vec<ModInt<M>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let len = a.len() + b.len() - 1;
+
+    if std::cmp::min(a.len(), b.len()) <= NAIVE_THRESHOLD {
+        let mut res = vec![ModInt::zero(); len];
+
+        for (i, &x) in a.iter().enumerate() {
+            for (slot, &y) in res[i..].iter_mut().zip(b) {
+                *slot += x * y;
+            }
+        }
+
+        return res;
+    }
+
+    let size = len.next_power_of_two();
+    let transform = |values: &[ModInt<M>]| {
+        let mut res = values.to_vec();
+        res.resize(size, ModInt::zero());
+        ntt(&mut res, false);
+        res
+    };
+
+    let mut res = transform(a);
+
+    for (x, y) in res.iter_mut().zip(transform(b)) {
+        *x *= y;
+    }
+
+    ntt(&mut res, true);
+    res.truncate(len);
+    res
+}
+
+/// Exact product of two polynomials with non-negative integer coefficients,
+/// computed modulo three primes. Coefficients of the result must be lower than
+/// about 7.8e25, for example when factors have 10^6 coefficients below 10^9.
+pub fn convolve_exact(a: &[u64], b: &[u64]) -> Vec<u128> {
+    fn convolve_in<const M: u64>(a: &[u64], b: &[u64]) -> Vec<ModInt<M>> {
+        let lift = |values: &[u64]| -> Vec<_> { values.iter().map(|&x| ModInt::new(x)).collect() };
+        convolve(&lift(a), &lift(b))
+    }
+
+    let (m1, m2, m3) = CRT_PRIMES;
+    let r1 = convolve_in::<{ CRT_PRIMES.0 }>(a, b);
+    let r2 = convolve_in::<{ CRT_PRIMES.1 }>(a, b);
+    let r3 = convolve_in::<{ CRT_PRIMES.2 }>(a, b);
+
+    // Garner's algorithm: x = x1 + m1·(x2 + m2·x3)
+    let inv_m1_m2 = ModInt::<{ CRT_PRIMES.1 }>::new(m1).inverse().unwrap();
+    let inv_m1_m3 = ModInt::<{ CRT_PRIMES.2 }>::new(m1).inverse().unwrap();
+    let inv_m2_m3 = ModInt::<{ CRT_PRIMES.2 }>::new(m2).inverse().unwrap();
+
+    (r1.into_iter().zip(r2).zip(r3))
+        .map(|((x1, x2), x3)| {
+            let x1 = x1.value();
+            let t2 = (x2 - ModInt::new(x1)) * inv_m1_m2;
+            let t3 = ((x3 - ModInt::new(x1)) * inv_m1_m3 - ModInt::new(t2.value())) * inv_m2_m3;
+            debug_assert!(t3.value() < m3);
+            u128::from(x1)
+                + u128::from(m1)
+                    * (u128::from(t2.value()) + u128::from(m2) * u128::from(t3.value()))
+        })
+        .collect()
+}
+
+/// Product of two polynomials modulo any `m`, with the same bounds as
+/// `convolve_exact` over coefficients reduced modulo `m`.
+pub fn convolve_mod(a: &[u64], b: &[u64], m: u64) -> Vec<u64> {
+    let reduce = |values: &[u64]| -> Vec<_> { values.iter().map(|x| x % m).collect() };
+
+    (convolve_exact(&reduce(a), &reduce(b)).into_iter())
+        .map(|x| (x % u128::from(m)) as u64)
+        .collect()
+}
+
+// ---
+// --- Formal power series
+// ---
+
+// Pad or truncate a series to exactly `n` terms.
+fn truncated<const M: u64>(mut values: Vec<ModInt<M>>, n: usize) -> Vec<ModInt<M>> {
+    values.resize(n, ModInt::zero());
+    values
+}
+
+// Inverses of integers in `1..n`, where the value for 0 is set to zero.
+fn inverses<const M: u64>(n: usize) -> Vec<ModInt<M>> {
+    let mut inv = vec![ModInt::zero(), ModInt::one()];
+
+    for i in 2..n as u64 {
+        // M = (M / i)·i + M % i
+        inv.push(-ModInt::new(M / i) * inv[(M % i) as usize]);
+    }
+
+    inv.truncate(n);
+    inv
+}
+
+/// First `n` terms of the inverse of a power series, which exists iff its
+/// constant term is not zero.
+pub fn inverse_series<const M: u64>(a: &[ModInt<M>], n: usize) -> Option<Vec<ModInt<M>>> {
+    let mut res = vec![a.first()?.inverse()?];
+
+    // Newton iteration: b ← b·(2 - a·b), which doubles the number of terms
+    while res.len() < n {
+        let len = std::cmp::min(2 * res.len(), n);
+        let prod = convolve(&a[..std::cmp::min(a.len(), len)], &res);
+        let mut correction: Vec<_> = truncated(prod, len).into_iter().map(|x| -x).collect();
+        correction[0] += ModInt::new(2);
+        res = truncated(convolve(&res, &correction), len);
+    }
+
+    Some(truncated(res, n))
+}
+
+/// First `n` terms of the logarithm of a power series, which must have 1 as
+/// constant term.
+pub fn log_series<const M: u64>(a: &[ModInt<M>], n: usize) -> Option<Vec<ModInt<M>>> {
+    if a.first() != Some(&ModInt::one()) {
+        return None;
+    }
+
+    if n == 0 {
+        return Some(Vec::new());
+    }
+
+    // log(a) is the integral of a' / a
+    let a = &a[..std::cmp::min(a.len(), n)];
+
+    let derivative: Vec<_> = (a.iter().enumerate().skip(1))
+        .map(|(i, &x)| ModInt::new(i as u64) * x)
+        .collect();
+
+    let quotient = convolve(&derivative, &inverse_series(a, n)?);
+    let inv = inverses(n);
+
+    let integral = std::iter::once(ModInt::zero())
+        .chain((quotient.into_iter().zip(&inv[1..])).map(|(x, &inv_i)| x * inv_i))
+        .collect();
+
+    Some(truncated(integral, n))
+}
+
+/// First `n` terms of the exponential of a power series, which must have 0 as
+/// constant term.
+pub fn exp_series<const M: u64>(a: &[ModInt<M>], n: usize) -> Option<Vec<ModInt<M>>> {
+    if a.first().is_some_and(|x| !x.is_zero()) {
+        return None;
+    }
+
+    let mut res = vec![ModInt::one()];
+
+    // Newton iteration: b ← b·(1 + a - log(b))
+    while res.len() < n {
+        let len = std::cmp::min(2 * res.len(), n);
+        let log = log_series(&res, len)?;
+
+        let mut correction: Vec<_> = (log.into_iter().enumerate())
+            .map(|(i, x)| a.get(i).copied().unwrap_or_else(ModInt::zero) - x)
+            .collect();
+
+        correction[0] += ModInt::one();
+        res = truncated(convolve(&res, &correction), len);
+    }
+
+    Some(truncated(res, n))
+}
+
+/// First `n` terms of the k-th power of a power series.
+pub fn pow_series<const M: u64>(a: &[ModInt<M>], k: u64, n: usize) -> Vec<ModInt<M>> {
+    if k == 0 {
+        return truncated(vec![ModInt::one()], n);
+    }
+
+    // Write a = c·x^t·(1 + x·b) to take the power of the last factor with
+    // a logarithm.
+    let t = match a.iter().position(|x| !x.is_zero()) {
+        Some(t) => t,
+        None => return truncated(Vec::new(), n),
+    };
+
+    let shift = match t.checked_mul(k as usize) {
+        Some(shift) if shift < n => shift,
+        _ => return truncated(Vec::new(), n),
+    };
+
+    let c = a[t];
+    let inv_c = c.inverse().expect("modulus must be prime");
+    let normalized: Vec<_> = a[t..].iter().map(|&x| x * inv_c).collect();
+
+    let log = log_series(&normalized, n - shift).unwrap();
+    let scaled: Vec<_> = log.into_iter().map(|x| x * ModInt::new(k)).collect();
+    let c_k = c.pow(k);
+
+    let res = std::iter::repeat_n(ModInt::zero(), shift)
+        .chain(
+            exp_series(&scaled, n - shift)
+                .unwrap()
+                .into_iter()
+                .map(|x| x * c_k),
+        )
+        .collect();
+
+    truncated(res, n)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::dp::partition_counts;
+
+    type F = ModInt<NTT_PRIME>;
+
+    fn series(values: &[i64]) -> Vec<F> {
+        values.iter().map(|&x| F::from(x)).collect()
+    }
+
+    // Deterministic pseudo-random values
+    fn noise(len: usize, seed: u64) -> Vec<u64> {
+        std::iter::successors(Some(seed), |x| {
+            Some(x.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1))
+        })
+        .map(|x| x >> 34)
+        .take(len)
+        .collect()
+    }
+
+    fn naive_convolve(a: &[u64], b: &[u64]) -> Vec<u128> {
+        let mut res = vec![0; (a.len() + b.len()).saturating_sub(1)];
+
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                res[i + j] += u128::from(x) * u128::from(y);
+            }
+        }
+
+        res
+    }
+
+    #[test]
+    fn test_ntt() {
+        let values: Vec<F> = noise(64, 1).into_iter().map(F::new).collect();
+        let mut transformed = values.clone();
+        ntt(&mut transformed, false);
+        assert_ne!(transformed, values);
+        ntt(&mut transformed, true);
+        assert_eq!(transformed, values);
+    }
+
+    #[test]
+    fn test_convolve() {
+        assert_eq!(
+            convolve(&series(&[1, 2]), &series(&[1, 3, -1])),
+            series(&[1, 5, 5, -2])
+        );
+
+        assert!(convolve::<NTT_PRIME>(&[], &series(&[1])).is_empty());
+
+        for (len_a, len_b) in [(1, 1), (33, 40), (100, 1000), (257, 255)] {
+            let (a, b) = (noise(len_a, 2), noise(len_b, 3));
+            let expected = naive_convolve(&a, &b);
+
+            let lift = |x: &[u64]| -> Vec<F> { x.iter().map(|&x| F::new(x)).collect() };
+            let modular: Vec<_> = (expected.iter())
+                .map(|&x| F::new((x % u128::from(NTT_PRIME)) as u64))
+                .collect();
+
+            assert_eq!(convolve(&lift(&a), &lift(&b)), modular);
+            assert_eq!(convolve_exact(&a, &b), expected);
+
+            let m = 1_000_000_007;
+            let expected_mod: Vec<_> = (expected.iter())
+                .map(|&x| (x % u128::from(m)) as u64)
+                .collect();
+
+            assert_eq!(convolve_mod(&a, &b, m), expected_mod);
+        }
+    }
+
+    #[test]
+    fn test_inverse_series() {
+        assert_eq!(inverse_series(&series(&[1, -1]), 5), Some(series(&[1; 5])));
+        assert_eq!(inverse_series(&series(&[0, 1]), 5), None);
+        assert_eq!(inverse_series(&series(&[2]), 0), Some(vec![]));
+
+        // Partitions are generated by the inverse of the product of (1 - x^k),
+        // which is a sum of ±x^k for generalized pentagonal numbers k.
+        let n = 10_000;
+        let mut euler = vec![F::zero(); n];
+
+        for k in 0..n as i64 {
+            for pentagonal in [k * (3 * k - 1) / 2, k * (3 * k + 1) / 2] {
+                if let Some(slot) = euler.get_mut(pentagonal as usize) {
+                    *slot = F::from(if k % 2 == 0 { 1i64 } else { -1 });
+                }
+            }
+        }
+
+        assert_eq!(
+            inverse_series(&euler, n).unwrap(),
+            partition_counts::<F>(n - 1)
+        );
+    }
+
+    #[test]
+    fn test_log_exp_series() {
+        // exp(x) = Σ x^k / k!
+        let exp = exp_series(&series(&[0, 1]), 8).unwrap();
+        let factorials = (0..8).scan(F::one(), |acc, k| {
+            let res = *acc;
+            *acc *= F::new(k + 1);
+            Some(res)
+        });
+
+        for (x, fact) in exp.iter().zip(factorials) {
+            assert_eq!(*x * fact, F::one());
+        }
+
+        // log(1 - x) = -Σ x^k / k
+        let log = log_series(&series(&[1, -1]), 6).unwrap();
+        assert!((1..6).all(|k| log[k] * F::new(k as u64) == -F::one()));
+
+        let a: Vec<F> = std::iter::once(F::zero())
+            .chain(noise(999, 4).into_iter().map(F::new))
+            .collect();
+
+        let exp = exp_series(&a, 1000).unwrap();
+        assert_eq!(log_series(&exp, 1000), Some(a));
+
+        assert_eq!(exp_series(&series(&[1, 1]), 3), None);
+        assert_eq!(log_series(&series(&[2, 1]), 3), None);
+    }
+
+    #[test]
+    fn test_pow_series() {
+        let binomials = series(&[1, 5, 10, 10, 5, 1, 0]);
+        assert_eq!(pow_series(&series(&[1, 1]), 5, 7), binomials);
+        assert_eq!(
+            pow_series(&series(&[0, 0, 1, 1]), 5, 13)[10..],
+            binomials[..3]
+        );
+        assert_eq!(pow_series(&series(&[0, 0, 1, 1]), 5, 10), series(&[0; 10]));
+        assert_eq!(pow_series(&series(&[0, 3]), 0, 3), series(&[1, 0, 0]));
+        assert_eq!(pow_series(&series(&[0]), 3, 2), series(&[0, 0]));
+        assert_eq!(
+            pow_series(&series(&[2, 2]), 3, 5),
+            series(&[8, 24, 24, 8, 0])
+        );
+
+        // Compare with repeated products
+        let a: Vec<F> = noise(50, 5).into_iter().map(F::new).collect();
+        let cube = truncated(convolve(&convolve(&a, &a), &a), 100);
+        assert_eq!(pow_series(&a, 3, 100), cube);
+    }
+}
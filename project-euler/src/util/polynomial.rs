@@ -0,0 +1,425 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use num_traits::{One, Zero};
+
+// ---
+// --- Polynomial
+// ---
+
+/// Polynomial given by its coefficients, from lowest to highest degree. The
+/// list of coefficients never ends with a zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial<T> {
+    coefs: Vec<T>,
+}
+
+impl<T: Zero> Polynomial<T> {
+    pub fn new(mut coefs: Vec<T>) -> Self {
+        while coefs.last().map(Zero::is_zero).unwrap_or(false) {
+            coefs.pop();
+        }
+
+        Self { coefs }
+    }
+
+    pub fn constant(value: T) -> Self {
+        Self::new(vec![value])
+    }
+
+    pub fn coefs(&self) -> &[T] {
+        &self.coefs
+    }
+
+    pub fn into_coefs(self) -> Vec<T> {
+        self.coefs
+    }
+
+    /// Degree of the polynomial, which is None for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefs.len().checked_sub(1)
+    }
+
+    pub fn leading_coef(&self) -> Option<&T> {
+        self.coefs.last()
+    }
+}
+
+impl<T: Zero + One> Polynomial<T> {
+    /// The polynomial X.
+    pub fn x() -> Self {
+        Self::new(vec![T::zero(), T::one()])
+    }
+}
+
+impl<T> Polynomial<T>
+where
+    T: Clone + Zero + Mul<Output = T>,
+{
+    pub fn eval(&self, x: &T) -> T {
+        self.coefs
+            .iter()
+            .rev()
+            .fold(T::zero(), |acc, coef| acc * x.clone() + coef.clone())
+    }
+
+    pub fn derivative(&self) -> Self
+    where
+        T: One,
+    {
+        let mut factor = T::zero();
+
+        let coefs = (self.coefs.iter().skip(1))
+            .map(|coef| {
+                factor = factor.clone() + T::one();
+                coef.clone() * factor.clone()
+            })
+            .collect();
+
+        Self::new(coefs)
+    }
+}
+
+impl<T> Polynomial<T>
+where
+    T: Clone + Zero + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    /// Euclidean division of `self` by `rhs`, returns the quotient and the
+    /// remainder. Panics if `rhs` is zero.
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let rhs_degree = rhs.degree().expect("division by zero polynomial");
+        let rhs_leading = rhs.coefs[rhs_degree].clone();
+        let mut rem = self.coefs.clone();
+
+        if rem.len() <= rhs_degree {
+            return (Self::new(Vec::new()), self.clone());
+        }
+
+        let mut quot = vec![T::zero(); rem.len() - rhs_degree];
+
+        for i in (0..quot.len()).rev() {
+            let factor = rem[i + rhs_degree].clone() / rhs_leading.clone();
+
+            for (j, coef) in rhs.coefs.iter().enumerate() {
+                rem[i + j] = rem[i + j].clone() - factor.clone() * coef.clone();
+            }
+
+            quot[i] = factor;
+        }
+
+        rem.truncate(rhs_degree);
+        (Self::new(quot), Self::new(rem))
+    }
+}
+
+// ---
+// --- Interpolation
+// ---
+
+impl<T> Polynomial<T>
+where
+    T: Clone + Zero + One + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    /// Polynomial of lowest degree going through all given points, computed
+    /// using Lagrange's formula in O(n²).
+    pub fn lagrange(points: &[(T, T)]) -> Self {
+        // Product of (X - x_i) for all points
+        let full_product = points.iter().fold(Self::constant(T::one()), |acc, (x, _)| {
+            &acc * &Self::new(vec![T::zero() - x.clone(), T::one()])
+        });
+
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, (xi, yi))| {
+                let (basis, _) =
+                    full_product.div_rem(&Self::new(vec![T::zero() - xi.clone(), T::one()]));
+
+                let denominator = (points.iter().enumerate())
+                    .filter(|(j, _)| *j != i)
+                    .fold(T::one(), |acc, (_, (xj, _))| {
+                        acc * (xi.clone() - xj.clone())
+                    });
+
+                basis.scale(&(yi.clone() / denominator))
+            })
+            .fold(Self::zero(), |acc, term| &acc + &term)
+    }
+
+    /// Polynomial of lowest degree going through all given points, computed
+    /// using Newton's divided differences in O(n²).
+    pub fn newton(points: &[(T, T)]) -> Self {
+        let mut diffs: Vec<T> = points.iter().map(|(_, y)| y.clone()).collect();
+
+        for level in 1..points.len() {
+            for i in (level..points.len()).rev() {
+                diffs[i] = (diffs[i].clone() - diffs[i - 1].clone())
+                    / (points[i].0.clone() - points[i - level].0.clone());
+            }
+        }
+
+        // Horner's scheme over the Newton basis
+        (0..points.len()).rev().fold(Self::zero(), |acc, i| {
+            let factor = Self::new(vec![T::zero() - points[i].0.clone(), T::one()]);
+            &(&acc * &factor) + &Self::constant(diffs[i].clone())
+        })
+    }
+
+    /// Polynomial P of lowest degree such that P(k) = values[k - 1] for each
+    /// k, which matches how Project Euler usually indexes sequences.
+    pub fn fit(values: &[T]) -> Self {
+        let mut x = T::zero();
+
+        let points: Vec<_> = values
+            .iter()
+            .map(|y| {
+                x = x.clone() + T::one();
+                (x.clone(), y.clone())
+            })
+            .collect();
+
+        Self::newton(&points)
+    }
+}
+
+impl<T: Clone + Zero + Mul<Output = T>> Polynomial<T> {
+    fn scale(&self, factor: &T) -> Self {
+        Self::new(
+            self.coefs
+                .iter()
+                .map(|c| c.clone() * factor.clone())
+                .collect(),
+        )
+    }
+}
+
+// ---
+// --- Finite differences
+// ---
+
+/// Table of iterated differences of a sequence, the first row being the
+/// sequence itself.
+pub fn difference_table<T>(values: &[T]) -> Vec<Vec<T>>
+where
+    T: Clone + Sub<Output = T>,
+{
+    let mut table = vec![values.to_vec()];
+
+    while table.last().unwrap().len() > 1 {
+        let row = table.last().unwrap();
+        let next = row
+            .windows(2)
+            .map(|w| w[1].clone() - w[0].clone())
+            .collect();
+        table.push(next);
+    }
+
+    table
+}
+
+/// Extend a sequence with the polynomial of lowest degree that matches given
+/// values, only the new terms are yielded.
+pub fn extrapolate<T>(values: &[T]) -> impl Iterator<Item = T>
+where
+    T: Clone + Add<Output = T> + Sub<Output = T>,
+{
+    // Only the last value of each row needs to be remembered
+    let mut lasts: Vec<T> = (difference_table(values).into_iter())
+        .filter_map(|row| row.last().cloned())
+        .collect();
+
+    std::iter::from_fn(move || {
+        for i in (0..lasts.len().checked_sub(1)?).rev() {
+            lasts[i] = lasts[i].clone() + lasts[i + 1].clone();
+        }
+
+        lasts.first().cloned()
+    })
+}
+
+// ---
+// --- Operators
+// ---
+
+impl<T: Zero> Zero for Polynomial<T>
+where
+    for<'a> &'a Polynomial<T>: Add<Output = Polynomial<T>>,
+{
+    fn zero() -> Self {
+        Self::new(Vec::new())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.coefs.is_empty()
+    }
+}
+
+impl<T: Clone + Zero + Add<Output = T>> Add for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let len = std::cmp::max(self.coefs.len(), rhs.coefs.len());
+
+        let coefs = (0..len)
+            .map(|i| {
+                let lhs = self.coefs.get(i).cloned().unwrap_or_else(T::zero);
+                let rhs = rhs.coefs.get(i).cloned().unwrap_or_else(T::zero);
+                lhs + rhs
+            })
+            .collect();
+
+        Polynomial::new(coefs)
+    }
+}
+
+impl<T: Clone + Zero + Sub<Output = T>> Sub for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let len = std::cmp::max(self.coefs.len(), rhs.coefs.len());
+
+        let coefs = (0..len)
+            .map(|i| {
+                let lhs = self.coefs.get(i).cloned().unwrap_or_else(T::zero);
+                let rhs = rhs.coefs.get(i).cloned().unwrap_or_else(T::zero);
+                lhs - rhs
+            })
+            .collect();
+
+        Polynomial::new(coefs)
+    }
+}
+
+impl<T: Clone + Zero + Mul<Output = T>> Mul for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.coefs.is_empty() || rhs.coefs.is_empty() {
+            return Polynomial::new(Vec::new());
+        }
+
+        let mut coefs = vec![T::zero(); self.coefs.len() + rhs.coefs.len() - 1];
+
+        for (i, x) in self.coefs.iter().enumerate() {
+            for (j, y) in rhs.coefs.iter().enumerate() {
+                coefs[i + j] = coefs[i + j].clone() + x.clone() * y.clone();
+            }
+        }
+
+        Polynomial::new(coefs)
+    }
+}
+
+impl<T: Clone + Zero + Neg<Output = T>> Neg for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn neg(self) -> Self::Output {
+        Polynomial::new(self.coefs.iter().map(|c| -c.clone()).collect())
+    }
+}
+
+macro_rules! impl_owned_binop {
+    ( $( $trait: ident :: $method: ident ),* ) => {
+        $(
+            impl<T> $trait for Polynomial<T>
+            where
+                for<'a> &'a Polynomial<T>: $trait<Output = Polynomial<T>>,
+            {
+                type Output = Polynomial<T>;
+
+                fn $method(self, rhs: Self) -> Self::Output {
+                    (&self).$method(&rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_owned_binop!(Add::add, Sub::sub, Mul::mul);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::modular::ModInt;
+    use num_rational::{BigRational, Rational64};
+
+    fn poly(coefs: &[i64]) -> Polynomial<Rational64> {
+        Polynomial::new(coefs.iter().map(|&c| c.into()).collect())
+    }
+
+    #[test]
+    fn test_operators() {
+        let p = poly(&[1, 2, 3]);
+        let q = poly(&[-1, 0, -3]);
+        assert_eq!(&p + &q, poly(&[0, 2]));
+        assert_eq!(&p - &p, Polynomial::zero());
+        assert_eq!(p.clone() * q, poly(&[-1, -2, -6, -6, -9]));
+        assert_eq!(-&p, poly(&[-1, -2, -3]));
+        assert_eq!(p.degree(), Some(2));
+        assert_eq!(poly(&[0, 0]).degree(), None);
+    }
+
+    #[test]
+    fn test_eval_and_derivative() {
+        let p = poly(&[1, -1, 0, 2]);
+        assert_eq!(p.eval(&3.into()), 52.into());
+        assert_eq!(p.derivative(), poly(&[-1, 0, 6]));
+        assert_eq!(poly(&[5]).derivative(), Polynomial::zero());
+    }
+
+    #[test]
+    fn test_div_rem() {
+        // (X³ - 2X² - 4) = (X - 3)(X² + X + 3) + 5
+        let (quot, rem) = poly(&[-4, 0, -2, 1]).div_rem(&poly(&[-3, 1]));
+        assert_eq!(quot, poly(&[3, 1, 1]));
+        assert_eq!(rem, poly(&[5]));
+
+        let (quot, rem) = poly(&[1, 2]).div_rem(&poly(&[0, 0, 1]));
+        assert_eq!(quot, Polynomial::zero());
+        assert_eq!(rem, poly(&[1, 2]));
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let p = poly(&[1, -1, 1, -1, 1]);
+        let points: Vec<(Rational64, Rational64)> =
+            (-2..3).map(|x| (x.into(), p.eval(&x.into()))).collect();
+
+        assert_eq!(Polynomial::lagrange(&points), p);
+        assert_eq!(Polynomial::newton(&points), p);
+        assert_eq!(Polynomial::<Rational64>::newton(&[]), Polynomial::zero());
+
+        type F = ModInt<1_000_000_007>;
+        let points: Vec<(F, F)> = (1..=4)
+            .map(|x: u64| (x.into(), (x * x * x).into()))
+            .collect();
+        let cubic = Polynomial::new(vec![F::zero(), F::zero(), F::zero(), F::one()]);
+        assert_eq!(Polynomial::lagrange(&points), cubic);
+        assert_eq!(Polynomial::newton(&points), cubic);
+
+        let values: Vec<BigRational> = (1..=4)
+            .map(|x| BigRational::from_integer((x * x).into()))
+            .collect();
+        let fit = Polynomial::fit(&values);
+        assert_eq!(
+            fit.eval(&BigRational::from_integer(10.into())),
+            BigRational::from_integer(100.into())
+        );
+    }
+
+    #[test]
+    fn test_finite_differences() {
+        let table = difference_table(&[1, 4, 9, 16]);
+        assert_eq!(
+            table,
+            vec![vec![1, 4, 9, 16], vec![3, 5, 7], vec![2, 2], vec![0]]
+        );
+
+        let next: Vec<_> = extrapolate(&[1, 8, 27]).take(3).collect();
+        assert_eq!(next, [58, 101, 156]);
+
+        let next: Vec<_> = extrapolate(&[1, 8, 27, 64]).take(2).collect();
+        assert_eq!(next, [125, 216]);
+
+        assert_eq!(extrapolate::<i64>(&[]).next(), None);
+    }
+}
@@ -1,65 +1,771 @@
-use cached::proc_macro::cached;
-use rayon::prelude::*;
-use std::convert::TryInto;
-use std::sync::atomic::{AtomicBool, Ordering};
-
-#[cached]
-fn sieve(max: u64) -> Vec<bool> {
-    assert!(max.is_power_of_two());
-
-    let max: usize = max.try_into().unwrap();
-    let mut prime: Vec<AtomicBool> = (0..=max).map(|_| true.into()).collect();
-    // let mut prime = vec![true; max + 1];
-    prime[0] = false.into();
-    prime[1] = false.into();
-
-    (2..=max).collect::<Vec<_>>().into_par_iter().for_each(|x| {
-        if prime[x].load(Ordering::Acquire) {
-            prime[x..]
-                .iter()
-                .step_by(x)
-                .skip(1)
-                .for_each(|v| v.store(false, Ordering::Release));
+use std::ops::{Mul, Range, Sub, SubAssign};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use num_bigint::BigUint;
+use num_integer::{Integer, Roots};
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::util::modular::{add_mod, pow_mod, sub_mod, MulMod};
+
+// ---
+// --- Segmented sieve
+// ---
+
+// Number of odd integers covered by a single segment, which is stored as a
+// bitset of 32KiB.
+const SEGMENT_ODDS: u64 = 1 << 18;
+
+// Mark odd composites of the odd integers `lo, lo + 2, ..., lo + 2 * (len - 1)`
+// into `bits`, a bit is set to 1 iff the integer is not a prime. The slice
+// `base` must contain all odd primes up to the square root of the last integer.
+fn sieve_segment(lo: u64, len: u64, base: &[u64], bits: &mut Vec<u64>) {
+    debug_assert!(lo % 2 == 1 && len > 0);
+    let last = lo + 2 * (len - 1);
+
+    bits.clear();
+    bits.resize(len.div_ceil(64) as usize, 0);
+
+    if lo == 1 {
+        bits[0] |= 1;
+    }
+
+    for &p in base.iter().take_while(|&&p| p * p <= last) {
+        let first_multiple = lo.div_ceil(p).checked_mul(p);
+        let first_odd_multiple = first_multiple.and_then(|m| {
+            if m % 2 == 0 {
+                m.checked_add(p)
+            } else {
+                Some(m)
+            }
+        });
+
+        let multiple = match first_odd_multiple {
+            Some(multiple) => std::cmp::max(p * p, multiple),
+            None => continue,
+        };
+
+        let mut index = (multiple - lo) / 2;
+
+        while index < len {
+            bits[(index / 64) as usize] |= 1 << (index % 64);
+            index += p;
+        }
+    }
+}
+
+// Iterate over indices of zero bits in `bits` that are lower than `len`.
+fn unset_bits(bits: &[u64], len: u64) -> impl Iterator<Item = u64> + '_ {
+    bits.iter().enumerate().flat_map(move |(i, &word)| {
+        let mut word = !word;
+
+        std::iter::from_fn(move || {
+            if word == 0 {
+                return None;
+            }
+
+            let bit = word.trailing_zeros();
+            word &= word - 1;
+            Some(64 * i as u64 + u64::from(bit))
+        })
+        .take_while(move |&index| index < len)
+    })
+}
+
+// Odd primes that are required to sieve a segment, they are computed lazily
+// as the segments move forward.
+#[derive(Default)]
+struct BasePrimes {
+    primes: Vec<u64>,
+    // All odd primes up to this value have been computed.
+    bound: u64,
+}
+
+impl BasePrimes {
+    fn ensure(&mut self, bound: u64) {
+        if bound <= std::cmp::max(self.bound, 2) {
+            return;
+        }
+
+        // Grow geometrically to avoid sieving tiny ranges over and over.
+        let bound = std::cmp::max(bound, 2 * self.bound);
+        self.ensure(bound.sqrt());
+
+        let mut bits = Vec::new();
+        let mut lo = (self.bound + 1) | 1;
+
+        while lo <= bound {
+            let len = std::cmp::min(SEGMENT_ODDS, (bound - lo) / 2 + 1);
+            sieve_segment(lo, len, &self.primes, &mut bits);
+            let primes = unset_bits(&bits, len).map(|i| lo + 2 * i);
+            self.primes.extend(primes);
+            lo += 2 * len;
         }
+
+        self.bound = bound;
+    }
+}
+
+/// Iterate over primes in given range, the memory usage only depends of the
+/// square root of the upper bound of the range.
+pub fn primes_in(range: Range<u64>) -> impl Iterator<Item = u64> {
+    let Range { start, end } = range;
+    let two = Some(2).filter(|_| (start..end).contains(&2));
+
+    let mut base = BasePrimes::default();
+    let mut bits = Vec::new();
+    let mut next_lo = start | 1;
+
+    let segments = std::iter::from_fn(move || {
+        if next_lo >= end {
+            return None;
+        }
+
+        let lo = next_lo;
+        let len = std::cmp::min(SEGMENT_ODDS, (end - lo).div_ceil(2));
+        let last = lo + 2 * (len - 1);
+        base.ensure(last.sqrt());
+        sieve_segment(lo, len, &base.primes, &mut bits);
+        next_lo = last + 2;
+
+        let primes: Vec<_> = unset_bits(&bits, len).map(|i| lo + 2 * i).collect();
+        Some(primes)
     });
 
-    prime.into_iter().map(|b| b.into_inner()).collect()
+    two.into_iter().chain(segments.flatten())
 }
 
+// ---
+// --- Shared prime table
+// ---
+
+// Minimal number of odd integers covered by the shared table once it is not
+// empty.
+const MIN_TABLE_ODDS: u64 = 1 << 15;
+
+// Number of primes copied at once by an iterator over the table.
+const ITER_CHUNK: usize = 1 << 12;
+
+/// Table of all primes bellow some bound, which only grows by sieving the
+/// integers that it doesn't cover yet.
+struct PrimeTable {
+    // Minimal number of odd integers covered once the table is not empty.
+    min_odds: u64,
+    // Bit i is set iff 2i + 1 is not a prime, its length is a multiple of 64.
+    composite_odds: Vec<u64>,
+    primes: Vec<u64>,
+}
+
+impl PrimeTable {
+    const fn new(min_odds: u64) -> Self {
+        Self {
+            min_odds,
+            composite_odds: Vec::new(),
+            primes: Vec::new(),
+        }
+    }
+
+    /// All integers bellow this bound are covered by the table.
+    fn bound(&self) -> u64 {
+        128 * self.composite_odds.len() as u64
+    }
+
+    fn is_prime(&self, n: u64) -> Option<bool> {
+        if n >= self.bound() {
+            return None;
+        }
+
+        if n.is_multiple_of(2) {
+            return Some(n == 2);
+        }
+
+        let i = n / 2;
+        Some(self.composite_odds[(i / 64) as usize] & (1 << (i % 64)) == 0)
+    }
+
+    fn nth_prime(&self, n: usize) -> Option<u64> {
+        self.primes.get(n.checked_sub(1)?).copied()
+    }
+
+    fn prime_index(&self, p: u64) -> Option<usize> {
+        self.primes.binary_search(&p).ok().map(|index| index + 1)
+    }
+
+    /// Primes of the table up to `max`.
+    fn primes_bellow(&self, max: u64) -> &[u64] {
+        &self.primes[..self.primes.partition_point(|&p| p <= max)]
+    }
+
+    fn ensure(&mut self, max: u64) {
+        if max < self.bound() {
+            return;
+        }
+
+        let new_nb_odds = (max / 2 + 1)
+            .max(128 * self.composite_odds.len() as u64)
+            .max(self.min_odds)
+            .next_multiple_of(64);
+
+        let last = 2 * new_nb_odds - 1;
+        let mut small_base = BasePrimes::default();
+
+        // Odd primes of the table are used to sieve new segments if there are
+        // enough of them.
+        if self.primes.is_empty() {
+            small_base.ensure(last.sqrt());
+        } else if last.sqrt() >= self.bound() {
+            self.ensure(last.sqrt());
+        }
+
+        // The table may have been extended to sieve its own base primes
+        let nb_odds = 64 * self.composite_odds.len() as u64;
+        let mut bits = Vec::new();
+        let mut lo = 2 * nb_odds + 1;
+
+        while lo <= last {
+            let len = std::cmp::min(SEGMENT_ODDS, (last - lo) / 2 + 1);
+
+            let base = match self.primes.get(1..) {
+                Some(odd_primes) if small_base.primes.is_empty() => odd_primes,
+                _ => &small_base.primes,
+            };
+
+            sieve_segment(lo, len, base, &mut bits);
+
+            if lo == 1 {
+                self.primes.push(2);
+            }
+
+            let primes = unset_bits(&bits, len).map(|i| lo + 2 * i);
+            self.primes.extend(primes);
+            self.composite_odds.extend_from_slice(&bits);
+            lo += 2 * len;
+        }
+    }
+}
+
+static PRIME_TABLE: RwLock<PrimeTable> = RwLock::new(PrimeTable::new(MIN_TABLE_ODDS));
+
+// Bound of the shared table, which is published after each extension so that
+// queries beyond the table don't need to lock it.
+static PRIME_TABLE_BOUND: AtomicU64 = AtomicU64::new(0);
+
+fn shared_table_bound() -> u64 {
+    PRIME_TABLE_BOUND.load(Ordering::Acquire)
+}
+
+fn extend_shared_table(table: &mut PrimeTable, max: u64) {
+    table.ensure(max);
+    PRIME_TABLE_BOUND.store(table.bound(), Ordering::Release);
+}
+
+/// Run a query over the shared table, which is first extended to cover all
+/// integers up to `max`.
+fn with_prime_table<T>(max: u64, query: impl Fn(&PrimeTable) -> T) -> T {
+    if max < shared_table_bound() {
+        return query(&PRIME_TABLE.read().unwrap());
+    }
+
+    let mut table = PRIME_TABLE.write().unwrap();
+    extend_shared_table(&mut table, max);
+    query(&table)
+}
+
+/// Extend the shared prime table so that `is_prime` runs in O(1) for any
+/// integer up to `max`.
+pub fn cache_primes(max: u64) {
+    with_prime_table(max, |_| ())
+}
+
+/// The n-th prime number, starting from `nth_prime(1) = 2`.
+pub fn nth_prime(n: usize) -> u64 {
+    assert!(n > 0, "primes are indexed from 1");
+
+    // Upper bound of p_n by Rosser's theorem, which holds for n >= 6
+    let x = n.max(6) as f64;
+    let bound = (x * (x.ln() + x.ln().ln())).ceil() as u64;
+    with_prime_table(bound, |table| table.nth_prime(n).unwrap())
+}
+
+/// Index of a prime number such that `nth_prime(prime_index(p)) = p`, returns
+/// None if `p` is not a prime. Primes beyond the shared table are counted
+/// without extending it.
+pub fn prime_index(p: u64) -> Option<usize> {
+    if p >= shared_table_bound() {
+        return Some(p)
+            .filter(|&p| is_prime(p))
+            .map(|p| prime_count(p) as usize);
+    }
+
+    PRIME_TABLE.read().unwrap().prime_index(p)
+}
+
+/// Iterator over primes backed by the shared table.
+pub struct Primes {
+    // Index in the table of the first prime after `buffer`.
+    index: usize,
+    buffer: std::vec::IntoIter<u64>,
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if let Some(p) = self.buffer.next() {
+            return Some(p);
+        }
+
+        let copy_chunk = |table: &PrimeTable| -> Vec<u64> {
+            let end = std::cmp::min(self.index + ITER_CHUNK, table.primes.len());
+            table.primes.get(self.index..end).unwrap_or(&[]).to_vec()
+        };
+
+        // The read lock must be released before locking for writing
+        let mut chunk = copy_chunk(&PRIME_TABLE.read().unwrap());
+
+        if chunk.is_empty() {
+            let mut table = PRIME_TABLE.write().unwrap();
+
+            while table.primes.len() <= self.index {
+                let bound = table.bound();
+                extend_shared_table(&mut table, bound);
+            }
+
+            chunk = copy_chunk(&table);
+        }
+
+        self.index += chunk.len();
+        self.buffer = chunk.into_iter();
+        self.buffer.next()
+    }
+}
+
+/// Iterate over all primes, each integer is sieved only once for the whole
+/// program.
+pub fn primes() -> Primes {
+    Primes {
+        index: 0,
+        buffer: Vec::new().into_iter(),
+    }
+}
+
+/// Iterate over primes up to `max`, which are read from the shared table if it
+/// already covers them and are streamed by a segmented sieve otherwise.
 pub fn primes_bellow(max: u64) -> impl Iterator<Item = u64> {
-    sieve(max.next_power_of_two())
-        .into_iter()
-        .enumerate()
-        .filter(|(_, prime)| *prime)
-        .map(|(x, _)| x.try_into().unwrap())
+    let (cached, streamed) = if max < shared_table_bound() {
+        let cached = PRIME_TABLE.read().unwrap().primes_bellow(max).to_vec();
+        (Some(cached), None)
+    } else {
+        (None, Some(primes_in(2..max.saturating_add(1))))
+    };
+
+    (cached.into_iter().flatten()).chain(streamed.into_iter().flatten())
 }
 
-pub fn primes() -> impl Iterator<Item = u64> {
-    let mut prev_max = 0;
-    let mut max = 256;
+// ---
+// --- Miller-Rabin
+// ---
 
-    std::iter::from_fn(move || {
-        let res = primes_bellow(max)
-            .into_iter()
-            .skip_while(move |p| *p <= prev_max);
+const SMALL_PRIMES: [u64; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
 
-        // Double the size of the sieve for next iteration
-        prev_max = max;
-        max *= 2;
+// Bases that are known to make Miller-Rabin deterministic for any input lower
+// than the associated bound.
+const DETERMINISTIC_BASES: [(u128, &[u128]); 3] = [
+    (4_759_123_141, &[2, 7, 61]),
+    (1 << 64, &[2, 325, 9375, 28178, 450775, 9780504, 1795265022]),
+    (
+        3_317_044_064_679_887_385_961_981,
+        &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41],
+    ),
+];
 
-        Some(res)
-    })
-    .flatten()
+// Check if odd `n` is a strong probable prime to base `a`.
+fn is_strong_probable_prime(n: u128, a: u128) -> bool {
+    let a = a % n;
+
+    if a == 0 {
+        return true;
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mut x = pow_mod(&a, &d, &n);
+
+    if x == 1 || x == n - 1 {
+        return true;
+    }
+
+    for _ in 1..s {
+        x = x.mul_mod(&x, &n);
+
+        if x == n - 1 {
+            return true;
+        }
+    }
+
+    false
 }
 
+fn jacobi(mut a: u128, mut n: u128) -> i8 {
+    debug_assert!(n % 2 == 1);
+    let mut res = 1;
+    a %= n;
+
+    while a != 0 {
+        while a.is_multiple_of(2) {
+            a /= 2;
+
+            if n % 8 == 3 || n % 8 == 5 {
+                res = -res;
+            }
+        }
+
+        std::mem::swap(&mut a, &mut n);
+
+        if a % 4 == 3 && n % 4 == 3 {
+            res = -res;
+        }
+
+        a %= n;
+    }
+
+    if n == 1 {
+        res
+    } else {
+        0
+    }
+}
+
+// Strong Lucas probable prime test with Selfridge's parameters, combined with
+// a base-2 Miller-Rabin test this is the Baillie-PSW test, for which no
+// counter-example is known.
+fn is_strong_lucas_probable_prime(n: u128) -> bool {
+    if n.sqrt().pow(2) == n {
+        return false;
+    }
+
+    // Find D in 5, -7, 9, -11, ... such that (D/n) = -1
+    let mut d: i128 = 5;
+
+    let d_mod_n = loop {
+        let d_mod_n = if d > 0 {
+            d as u128 % n
+        } else {
+            sub_mod(&0, &(d.unsigned_abs() % n), &n)
+        };
+
+        match jacobi(d_mod_n, n) {
+            -1 => break d_mod_n,
+            0 if d.unsigned_abs() != n => return false,
+            _ => d = if d > 0 { -d - 2 } else { -d + 2 },
+        }
+    };
+
+    // P = 1 and Q = (1 - D) / 4
+    let q = if d > 0 {
+        sub_mod(&0, &((d as u128 - 1) / 4 % n), &n)
+    } else {
+        (d.unsigned_abs() + 1) / 4 % n
+    };
+
+    let half = |x: u128| {
+        if x.is_multiple_of(2) {
+            x / 2
+        } else {
+            x / 2 + n / 2 + 1
+        }
+    };
+
+    let s = (n + 1).trailing_zeros();
+    let k = (n + 1) >> s;
+    let (mut u, mut v, mut qk) = (1, 1, q);
+
+    for bit in (0..127 - k.leading_zeros()).rev() {
+        u = u.mul_mod(&v, &n);
+        v = sub_mod(&v.mul_mod(&v, &n), &add_mod(&qk, &qk, &n), &n);
+        qk = qk.mul_mod(&qk, &n);
+
+        if (k >> bit) & 1 == 1 {
+            let (u_prev, v_prev) = (u, v);
+            u = half(add_mod(&u_prev, &v_prev, &n));
+            v = half(add_mod(&d_mod_n.mul_mod(&u_prev, &n), &v_prev, &n));
+            qk = qk.mul_mod(&q, &n);
+        }
+    }
+
+    if u == 0 || v == 0 {
+        return true;
+    }
+
+    for _ in 1..s {
+        v = sub_mod(&v.mul_mod(&v, &n), &add_mod(&qk, &qk, &n), &n);
+        qk = qk.mul_mod(&qk, &n);
+
+        if v == 0 {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Deterministic primality test, which is exact for any `u64` and runs in
+/// O(1) for integers covered by the shared prime table.
 pub fn is_prime(n: u64) -> bool {
-    sieve(n.next_power_of_two())[n as usize]
+    // The table only grows, integers bellow its published bound are covered
+    if n < shared_table_bound() {
+        if let Some(res) = PRIME_TABLE.read().unwrap().is_prime(n) {
+            return res;
+        }
+    }
+
+    is_prime_u128(n.into())
+}
+
+/// Primality test which is proven to be exact up to 3.3e24, above this bound
+/// it relies on the Baillie-PSW test.
+pub fn is_prime_u128(n: u128) -> bool {
+    for &p in &SMALL_PRIMES {
+        let p = u128::from(p);
+
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    if n < 2 {
+        return false;
+    }
+
+    match DETERMINISTIC_BASES.iter().find(|(bound, _)| n < *bound) {
+        Some((_, bases)) => bases.iter().all(|&a| is_strong_probable_prime(n, a)),
+        None => is_strong_probable_prime(n, 2) && is_strong_lucas_probable_prime(n),
+    }
+}
+
+/// Miller-Rabin test over a fixed set of bases, a composite number may be
+/// reported as prime.
+pub fn is_probable_prime(n: &BigUint) -> bool {
+    if let Some(n) = n.to_u128() {
+        return is_prime_u128(n);
+    }
+
+    if SMALL_PRIMES.iter().any(|&p| (n % p).is_zero()) {
+        return false;
+    }
+
+    let n_minus_one = n - 1u8;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+
+    SMALL_PRIMES.iter().all(|&a| {
+        let mut x = BigUint::from(a).modpow(&d, n);
+
+        if x.is_one() || x == n_minus_one {
+            return true;
+        }
+
+        for _ in 1..s {
+            x = &x * &x % n;
+
+            if x == n_minus_one {
+                return true;
+            }
+        }
+
+        false
+    })
+}
+
+// ---
+// --- Factorization
+// ---
+
+// Pollard's rho algorithm with Brent's cycle detection, returns a non-trivial
+// divisor of `n` or None if the pseudo-random sequence seeded by `c` failed.
+fn pollard_brent(n: u128, c: u128) -> Option<u128> {
+    // Number of steps that are batched before computing a gcd
+    const BATCH: u128 = 128;
+
+    let f = |x: u128| add_mod(&x.mul_mod(&x, &n), &c, &n);
+    let (mut x, mut y, mut ys) = (2, 2, 2);
+    let (mut r, mut q, mut g) = (1, 1, 1);
+
+    while g == 1 {
+        x = y;
+
+        for _ in 0..r {
+            y = f(y);
+        }
+
+        let mut k = 0;
+
+        while k < r && g == 1 {
+            ys = y;
+
+            for _ in 0..std::cmp::min(BATCH, r - k) {
+                y = f(y);
+                q = q.mul_mod(&x.abs_diff(y), &n);
+            }
+
+            g = q.gcd(&n);
+            k += BATCH;
+        }
+
+        r *= 2;
+    }
+
+    if g == n {
+        // The batch overshot, walk back one step at a time
+        loop {
+            ys = f(ys);
+            g = x.abs_diff(ys).gcd(&n);
+
+            if g > 1 {
+                break;
+            }
+        }
+    }
+
+    Some(g).filter(|&g| g != n)
+}
+
+/// Decompose `n` into a sorted list of prime factors with their exponent.
+pub fn factorize_u128(mut n: u128) -> Vec<(u128, u32)> {
+    assert!(n > 0, "can't factorize 0");
+    let mut factors = Vec::new();
+
+    for &p in &SMALL_PRIMES {
+        let p = u128::from(p);
+
+        while n.is_multiple_of(p) {
+            factors.push(p);
+            n /= p;
+        }
+    }
+
+    let mut to_split = vec![n];
+
+    while let Some(m) = to_split.pop() {
+        if m == 1 {
+            continue;
+        }
+
+        if is_prime_u128(m) {
+            factors.push(m);
+            continue;
+        }
+
+        let divisor = (1..)
+            .find_map(|c| pollard_brent(m, c))
+            .expect("no seed found for Pollard's rho");
+
+        to_split.push(divisor);
+        to_split.push(m / divisor);
+    }
+
+    factors.sort_unstable();
+    let mut res: Vec<(u128, u32)> = Vec::new();
+
+    for p in factors {
+        match res.last_mut() {
+            Some((last, exp)) if *last == p => *exp += 1,
+            _ => res.push((p, 1)),
+        }
+    }
+
+    res
+}
+
+/// Decompose `n` into a sorted list of prime factors with their exponent.
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    factorize_u128(n.into())
+        .into_iter()
+        .map(|(p, exp)| (p as u64, exp))
+        .collect()
+}
+
+// ---
+// --- Prime counting
+// ---
+
+// Lucy_Hedgehog's algorithm: compute the sum of f(p) for primes p <= n, where
+// f is completely multiplicative, in O(n^(3/4)). The function `prefix(v)` must
+// give the sum of f(k) for 2 <= k <= v and `value(p)` must give f(p).
+fn lucy_hedgehog<T>(n: u64, prefix: impl Fn(u64) -> T, value: impl Fn(u64) -> T) -> T
+where
+    T: Copy + PartialEq + Mul<Output = T> + Sub<Output = T> + SubAssign,
+{
+    let r = n.sqrt();
+
+    if r < 2 {
+        return prefix(n);
+    }
+
+    // small[v] = S(v) and large[i] = S(n / i) for v, i <= r
+    let mut small: Vec<T> = (0..=r).map(&prefix).collect();
+    let mut large: Vec<T> = (0..=r).map(|i| prefix(n / i.max(1))).collect();
+
+    for p in 2..=r {
+        if small[p as usize] == small[p as usize - 1] {
+            continue;
+        }
+
+        let prev = small[p as usize - 1];
+        let fp = value(p);
+        let p2 = p * p;
+
+        for i in 1..=std::cmp::min(r, n / p2) {
+            let d = i * p;
+
+            let s = if d <= r {
+                large[d as usize]
+            } else {
+                small[(n / d) as usize]
+            };
+
+            large[i as usize] -= fp * (s - prev);
+        }
+
+        for v in (p2..=r).rev() {
+            let s = small[(v / p) as usize];
+            small[v as usize] -= fp * (s - prev);
+        }
+    }
+
+    large[1]
+}
+
+/// Number of primes lower or equal to `x`.
+pub fn prime_count(x: u64) -> u128 {
+    lucy_hedgehog(x, |v| v.saturating_sub(1), |_| 1).into()
+}
+
+/// Sum of primes lower or equal to `x`.
+pub fn prime_sum(x: u64) -> u128 {
+    lucy_hedgehog(
+        x,
+        |v| {
+            let v = u128::from(v);
+            (v * (v + 1) / 2).saturating_sub(1)
+        },
+        u128::from,
+    )
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    fn is_prime_naive(n: u64) -> bool {
+        n >= 2
+            && (2..)
+                .take_while(|d| d * d <= n)
+                .all(|d| !n.is_multiple_of(d))
+    }
+
     #[test]
     fn test_primes_bellow() {
         let start: Vec<_> = primes_bellow(13).collect();
@@ -70,11 +776,188 @@ mod test {
     fn test_primes() {
         let start: Vec<_> = primes().take(6).collect();
         assert_eq!(&start, &[2, 3, 5, 7, 11, 13]);
+        assert_eq!(primes().nth(100_000), Some(1_299_721));
+    }
+
+    #[test]
+    fn test_primes_in() {
+        for (lo, hi) in [
+            (0, 1000),
+            (1, 2),
+            (2, 3),
+            (3, 4),
+            (90, 97),
+            (90, 98),
+            (500, 2000),
+        ] {
+            let expected: Vec<_> = (lo..hi).filter(|n| is_prime_naive(*n)).collect();
+            assert_eq!(primes_in(lo..hi).collect::<Vec<_>>(), expected);
+        }
+
+        assert_eq!(primes_in(0..10_000_000).count(), 664_579);
+
+        let lo = 1_000_000_000_000;
+        let expected: Vec<_> = (lo..lo + 1000).filter(|n| is_prime_naive(*n)).collect();
+        assert_eq!(primes_in(lo..lo + 1000).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_prime_table() {
+        assert_eq!(nth_prime(1), 2);
+        assert_eq!(nth_prime(6), 13);
+        assert_eq!(nth_prime(10_001), 104_743);
+        assert_eq!(prime_index(2), Some(1));
+        assert_eq!(prime_index(104_743), Some(10_001));
+        assert_eq!(prime_index(104_741), None);
+
+        let mut table = PrimeTable::new(MIN_TABLE_ODDS);
+        table.ensure(100_000);
+        assert_eq!(
+            table.primes,
+            primes_in(0..table.bound()).collect::<Vec<_>>()
+        );
+        assert!((0..table.bound()).all(|n| table.is_prime(n) == Some(is_prime_naive(n))));
+        assert_eq!(table.is_prime(table.bound()), None);
+
+        // Extending the table only sieves new integers
+        table.ensure(3_000_000);
+        assert_eq!(
+            table.primes,
+            primes_in(0..table.bound()).collect::<Vec<_>>()
+        );
+
+        // Growing a table past the square of its bound requires to extend it
+        // to sieve its own base primes first.
+        let mut table = PrimeTable::new(64);
+        table.ensure(10);
+        let bound = table.bound();
+        table.ensure((bound + 1).pow(2) + 10);
+
+        let expected: Vec<_> = primes_in(0..table.bound()).collect();
+        assert_eq!(table.primes, expected);
+        assert_eq!(table.bound(), 128 * table.composite_odds.len() as u64);
+        assert!((0..table.bound()).all(|n| table.is_prime(n) == Some(is_prime_naive(n))));
+
+        for (i, &p) in expected.iter().enumerate() {
+            assert_eq!(table.nth_prime(i + 1), Some(p));
+            assert_eq!(table.prime_index(p), Some(i + 1));
+            assert_eq!(table.primes_bellow(p).len(), i + 1);
+        }
+
+        assert!((4..table.bound())
+            .step_by(2)
+            .all(|n| table.prime_index(n).is_none()));
+    }
+
+    #[test]
+    fn test_prime_table_concurrent() {
+        use rayon::prelude::*;
+
+        let counts: Vec<_> = (1..=16u64)
+            .into_par_iter()
+            .map(|k| {
+                cache_primes(k * 100_000);
+                let count = primes_bellow(k * 100_000).count();
+                assert_eq!(prime_index(nth_prime(count)), Some(count));
+                count as u128
+            })
+            .collect();
+
+        let expected: Vec<_> = (1..=16).map(|k| prime_count(k * 100_000)).collect();
+        assert_eq!(counts, expected);
+        assert!((0..1_000_000).all(|n| is_prime(n) == is_prime_u128(n.into())));
+    }
+
+    #[test]
+    fn test_prime_table_bounded() {
+        // Queries beyond the shared table don't extend it
+        assert_eq!(
+            primes_bellow(50_000_000).count() as u128,
+            prime_count(50_000_000)
+        );
+        assert_eq!(prime_index(1_000_000_007), Some(50_847_535));
+        assert_eq!(prime_index(1_000_000_008), None);
+        assert!(is_prime(1_000_000_000_000_037));
     }
 
     #[test]
     fn test_is_prime() {
         assert!(is_prime(13));
         assert!(!is_prime(42));
+        assert!(!is_prime(0));
+        assert!(!is_prime(1));
+        assert!(is_prime(2));
+        assert!(is_prime(1_000_000_007));
+        assert!(is_prime(18_446_744_073_709_551_557));
+        assert!(!is_prime(3_825_123_056_546_413_051));
+        assert!((0..10_000).all(|n| is_prime(n) == is_prime_naive(n)));
+    }
+
+    #[test]
+    fn test_is_prime_u128() {
+        assert!(is_prime_u128((1 << 127) - 1));
+        assert!(!is_prime_u128(3_317_044_064_679_887_385_961_981));
+        assert!(!is_prime_u128(((1 << 61) - 1) * ((1 << 61) - 1)));
+        assert!(!is_prime_u128(
+            1_000_000_000_000_000_003 * 1_000_000_000_000_000_009
+        ));
+        assert!(is_prime_u128(
+            170_141_183_460_469_231_731_687_303_715_884_105_757
+        ));
+    }
+
+    #[test]
+    fn test_is_probable_prime() {
+        let mersenne = |p: u32| (BigUint::one() << p) - 1u8;
+        assert!(is_probable_prime(&mersenne(521)));
+        assert!(!is_probable_prime(&mersenne(523)));
+        assert!(!is_probable_prime(&(mersenne(127) * mersenne(127))));
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert_eq!(factorize(1), []);
+        assert_eq!(factorize(2), [(2, 1)]);
+        assert_eq!(factorize(360), [(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(
+            factorize(600_851_475_143),
+            [(71, 1), (839, 1), (1471, 1), (6857, 1)]
+        );
+        assert_eq!(
+            factorize(u64::MAX),
+            [
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65537, 1),
+                (6_700_417, 1)
+            ]
+        );
+        assert_eq!(
+            factorize(4_294_967_291 * 4_294_967_279),
+            [(4_294_967_279, 1), (4_294_967_291, 1)]
+        );
+        assert_eq!(
+            factorize(1_000_000_007 * 1_000_000_007),
+            [(1_000_000_007, 2)]
+        );
+
+        assert_eq!(
+            factorize_u128(((1 << 61) - 1) * 1_000_003 * 1_000_033 * 1_000_033),
+            [(1_000_003, 1), (1_000_033, 2), ((1 << 61) - 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_prime_count() {
+        for x in (0..100).chain((100..100_000).step_by(997)) {
+            assert_eq!(prime_count(x), primes_bellow(x).count() as u128);
+            assert_eq!(prime_sum(x), primes_bellow(x).map(u128::from).sum());
+        }
+
+        assert_eq!(prime_count(1_000_000_000), 50_847_534);
+        assert_eq!(prime_sum(2_000_000), 142_913_828_922);
     }
 }
@@ -1,5 +1,15 @@
+use std::ops::{Div, Mul, Sub};
+
 use num_traits::{CheckedAdd, One, Zero};
 
+use crate::util::arithmetic::NbDigits;
+use crate::util::modular::{add_mod, sub_mod, MulMod};
+use crate::util::polynomial::Polynomial;
+
+// ---
+// --- Fibonacci
+// ---
+
 pub fn fibonacci<T: CheckedAdd + Clone + One + Zero>() -> impl Iterator<Item = T> {
     let (mut u, mut v) = (T::zero(), T::one());
 
@@ -9,3 +19,523 @@ pub fn fibonacci<T: CheckedAdd + Clone + One + Zero>() -> impl Iterator<Item = T
         Some(u.clone())
     })
 }
+
+/// Pair (F(n), F(n+1)) computed by fast doubling in O(log n) operations. The
+/// number type must be able to hold F(n+1).
+pub fn fibonacci_pair<T>(n: u64) -> (T, T)
+where
+    T: Clone + Zero + One + Sub<Output = T> + Mul<Output = T>,
+{
+    let (mut curr, mut next) = (T::zero(), T::one());
+
+    // Read bits of n from the highest, with (curr, next) = (F(k), F(k+1)) for
+    // k the bits read so far.
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        // F(2k) = F(k)·(2F(k+1) - F(k)) and F(2k+1) = F(k)² + F(k+1)²
+        let double = curr.clone() * (next.clone() + next.clone() - curr.clone());
+        let double_next = curr.clone() * curr + next.clone() * next;
+
+        if (n >> bit) & 1 == 1 {
+            curr = double_next.clone();
+            next = double + double_next;
+        } else {
+            curr = double;
+            next = double_next;
+        }
+    }
+
+    (curr, next)
+}
+
+pub fn fibonacci_nth<T>(n: u64) -> T
+where
+    T: Clone + Zero + One + Sub<Output = T> + Mul<Output = T>,
+{
+    fibonacci_pair(n).0
+}
+
+/// Lucas number L(n) = F(n - 1) + F(n + 1), with L(0) = 2.
+pub fn lucas_nth<T>(n: u64) -> T
+where
+    T: Clone + Zero + One + Sub<Output = T> + Mul<Output = T>,
+{
+    let (curr, next) = fibonacci_pair::<T>(n);
+    next.clone() + next - curr
+}
+
+/// F(n) modulo m, for any modulus that fits in a u64.
+pub fn fibonacci_mod(n: u64, m: u64) -> u64 {
+    let (mut curr, mut next) = (0, 1 % m);
+
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        let twice_next = add_mod(&next, &next, &m);
+        let double = curr.mul_mod(&sub_mod(&twice_next, &curr, &m), &m);
+        let double_next = add_mod(&curr.mul_mod(&curr, &m), &next.mul_mod(&next, &m), &m);
+
+        if (n >> bit) & 1 == 1 {
+            curr = double_next;
+            next = add_mod(&double, &double_next, &m);
+        } else {
+            curr = double;
+            next = double_next;
+        }
+    }
+
+    curr
+}
+
+// log10(φ) and log10(√5) as fixed-point numbers with 128 fractional bits
+const LOG10_PHI: u128 = 0x358036c8_2451b7f3_65d3db23_845599f5;
+const LOG10_SQRT_5: u128 = 0x5977d95e_c10c0219_dc1da994_fd20dba1;
+
+/// The `k` first digits of F(n), or all of them if it has less than `k`
+/// digits. The precision of the computation allows up to 12 digits.
+pub fn leading_digits(n: u64, k: u32) -> u64 {
+    assert!((1..=12).contains(&k), "can only compute 1 to 12 digits");
+
+    // F(186) is the largest term that fits into a u128, but computing F(n) also
+    // requires F(n + 1).
+    if n <= 185 {
+        let term: u128 = fibonacci_nth(n);
+        let nb_digits = term.nb_digits(10);
+        return (term / 10u128.pow(nb_digits.saturating_sub(k))) as u64;
+    }
+
+    approx_leading_digits(n, k)
+}
+
+fn approx_leading_digits(n: u64, k: u32) -> u64 {
+    // F(n) is the closest integer to φ^n/√5, so its digits are given by the
+    // fractional part of n·log10(φ) - log10(√5), which is computed modulo 1
+    // with fixed-point arithmetic to avoid a loss of precision for large n.
+    let frac = LOG10_PHI.wrapping_mul(n.into()).wrapping_sub(LOG10_SQRT_5);
+    let mantissa = 10f64.powf(frac as f64 / 2f64.powi(128));
+    (mantissa * 10f64.powi(k as i32 - 1)) as u64
+}
+
+/// The `k` last digits of F(n), as F(n) mod 10^k.
+pub fn trailing_digits(n: u64, k: u32) -> u64 {
+    fibonacci_mod(n, 10u64.pow(k))
+}
+
+// ---
+// --- Linear recurrences
+// ---
+
+/// Sequence defined by a_n = c_1·a_{n-1} + ... + c_L·a_{n-L} over a field, for
+/// example `ModInt<P>` or `Rational64`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearRecurrence<T> {
+    coefs: Vec<T>,
+    initial: Vec<T>,
+}
+
+impl<T> LinearRecurrence<T>
+where
+    T: Clone + Zero + One + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    /// Build a recurrence from its coefficients c_1, ..., c_L and its L first
+    /// terms.
+    pub fn new(coefs: Vec<T>, initial: Vec<T>) -> Self {
+        assert_eq!(
+            coefs.len(),
+            initial.len(),
+            "one initial term per coefficient"
+        );
+        Self { coefs, initial }
+    }
+
+    /// Find the shortest recurrence generating given terms, using
+    /// Berlekamp-Massey's algorithm. At least 2L terms are required to find a
+    /// recurrence of order L.
+    pub fn find(terms: impl IntoIterator<Item = T>) -> Self {
+        let terms: Vec<T> = terms.into_iter().collect();
+
+        // Connection polynomial C, such that sum of C[i]·a_{n-i} is zero, and
+        // its value B before the last length change.
+        let mut curr = vec![T::one()];
+        let mut prev = vec![T::one()];
+        let mut prev_discrepancy = T::one();
+        let mut len = 0;
+        let mut shift = 1;
+
+        for n in 0..terms.len() {
+            let discrepancy = (1..=len).fold(terms[n].clone(), |acc, i| {
+                acc + curr[i].clone() * terms[n - i].clone()
+            });
+
+            if discrepancy.is_zero() {
+                shift += 1;
+                continue;
+            }
+
+            let factor = discrepancy.clone() / prev_discrepancy.clone();
+            let old = curr.clone();
+
+            if curr.len() < prev.len() + shift {
+                curr.resize(prev.len() + shift, T::zero());
+            }
+
+            for (i, coef) in prev.iter().enumerate() {
+                curr[i + shift] = curr[i + shift].clone() - factor.clone() * coef.clone();
+            }
+
+            if 2 * len <= n {
+                len = n + 1 - len;
+                prev = old;
+                prev_discrepancy = discrepancy;
+                shift = 1;
+            } else {
+                shift += 1;
+            }
+        }
+
+        curr.resize(len + 1, T::zero());
+
+        let coefs = (curr.into_iter().skip(1)).map(|c| T::zero() - c).collect();
+
+        Self::new(coefs, terms.into_iter().take(len).collect())
+    }
+
+    pub fn order(&self) -> usize {
+        self.coefs.len()
+    }
+
+    pub fn coefs(&self) -> &[T] {
+        &self.coefs
+    }
+
+    /// Compute a_n in O(L² log n) with Kitamasa's method: a_n is obtained from
+    /// the remainder of X^n modulo the characteristic polynomial.
+    pub fn nth(&self, n: u64) -> T {
+        if let Some(term) = self.initial.get(n as usize) {
+            return term.clone();
+        }
+
+        let characteristic = Polynomial::new(
+            (self.coefs.iter().rev())
+                .map(|c| T::zero() - c.clone())
+                .chain(std::iter::once(T::one()))
+                .collect(),
+        );
+
+        let mul_mod = |x: &Polynomial<T>, y: &Polynomial<T>| (x * y).div_rem(&characteristic).1;
+        let mut res = Polynomial::constant(T::one());
+        let mut base = Polynomial::x().div_rem(&characteristic).1;
+        let mut exp = n;
+
+        while exp > 0 {
+            if exp % 2 == 1 {
+                res = mul_mod(&res, &base);
+            }
+
+            base = mul_mod(&base, &base);
+            exp /= 2;
+        }
+
+        (res.coefs().iter().zip(&self.initial))
+            .fold(T::zero(), |acc, (x, y)| acc + x.clone() * y.clone())
+    }
+}
+
+/// Compute the n-th term of a sequence given by enough of its first terms to
+/// find its minimal linear recurrence.
+pub fn linear_recurrence_nth<T>(terms: impl IntoIterator<Item = T>, n: u64) -> T
+where
+    T: Clone + Zero + One + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    LinearRecurrence::find(terms).nth(n)
+}
+
+// ---
+// --- Pythagorean triples
+// ---
+
+/// Integer sides of a right triangle, with a < b < c.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PythagoreanTriple {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+}
+
+impl PythagoreanTriple {
+    pub fn perimeter(&self) -> u64 {
+        self.a + self.b + self.c
+    }
+
+    pub fn hypotenuse(&self) -> u64 {
+        self.c
+    }
+
+    fn scale(&self, k: u64) -> Self {
+        Self {
+            a: k * self.a,
+            b: k * self.b,
+            c: k * self.c,
+        }
+    }
+}
+
+/// Iterator over primitive Pythagorean triples, which walks the tree of
+/// Berggren where the children of a triple are bigger than their parent.
+pub struct PrimitiveTriples {
+    max: u64,
+    key: fn(&PythagoreanTriple) -> u64,
+    // Triples from the tree, where a and b may not be sorted
+    stack: Vec<(u64, u64, u64)>,
+}
+
+impl PrimitiveTriples {
+    fn new(max: u64, key: fn(&PythagoreanTriple) -> u64) -> Self {
+        Self {
+            max,
+            key,
+            stack: vec![(3, 4, 5)],
+        }
+    }
+
+    /// Iterate over all triples, including multiples of primitive ones, in
+    /// no particular order.
+    pub fn with_multiples(self) -> impl Iterator<Item = PythagoreanTriple> {
+        let (max, key) = (self.max, self.key);
+        self.flat_map(move |triple| (1..=max / key(&triple)).map(move |k| triple.scale(k)))
+    }
+}
+
+impl Iterator for PrimitiveTriples {
+    type Item = PythagoreanTriple;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (a, b, c) = self.stack.pop()?;
+
+            let triple = PythagoreanTriple {
+                a: a.min(b),
+                b: a.max(b),
+                c,
+            };
+
+            if (self.key)(&triple) > self.max {
+                continue;
+            }
+
+            self.stack.extend([
+                (a + 2 * c - 2 * b, 2 * a + 2 * c - b, 2 * a + 3 * c - 2 * b),
+                (a + 2 * b + 2 * c, 2 * a + b + 2 * c, 2 * a + 2 * b + 3 * c),
+                (2 * b + 2 * c - a, b + 2 * c - 2 * a, 2 * b + 3 * c - 2 * a),
+            ]);
+
+            return Some(triple);
+        }
+    }
+}
+
+/// Primitive Pythagorean triples with a perimeter of at most `max_perimeter`.
+pub fn pythagorean_triples(max_perimeter: u64) -> PrimitiveTriples {
+    PrimitiveTriples::new(max_perimeter, PythagoreanTriple::perimeter)
+}
+
+/// Primitive Pythagorean triples with a hypotenuse of at most
+/// `max_hypotenuse`.
+pub fn pythagorean_triples_by_hypotenuse(max_hypotenuse: u64) -> PrimitiveTriples {
+    PrimitiveTriples::new(max_hypotenuse, PythagoreanTriple::hypotenuse)
+}
+
+/// Count triples for each value of a key such as the perimeter or the
+/// hypotenuse, in `0..=max`.
+pub fn count_triples_by(
+    triples: impl IntoIterator<Item = PythagoreanTriple>,
+    max: u64,
+    key: impl Fn(&PythagoreanTriple) -> u64,
+) -> Vec<u32> {
+    let mut counts = vec![0; max as usize + 1];
+
+    for triple in triples {
+        if let Some(count) = counts.get_mut(key(&triple) as usize) {
+            *count += 1;
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::linalg::Matrix;
+    use crate::util::modular::ModInt;
+    use num_bigint::BigUint;
+    use num_rational::Rational64;
+
+    type F = ModInt<998_244_353>;
+
+    #[test]
+    fn test_fast_fibonacci() {
+        let slow: Vec<u64> = std::iter::once(0).chain(fibonacci()).take(93).collect();
+
+        for (n, term) in slow.iter().enumerate().take(92) {
+            assert_eq!(fibonacci_nth::<u64>(n as u64), *term);
+            assert_eq!(fibonacci_mod(n as u64, 1_000_007), term % 1_000_007);
+        }
+
+        assert_eq!(
+            fibonacci_nth::<u128>(185),
+            205697230343233228174223751303346572685
+        );
+        assert_eq!(
+            fibonacci_nth::<BigUint>(300).to_string(),
+            "222232244629420445529739893461909967206666939096499764990979600"
+        );
+
+        let lucas: Vec<u64> = (0..8).map(lucas_nth).collect();
+        assert_eq!(lucas, [2, 1, 3, 4, 7, 11, 18, 29]);
+
+        let n = 1_000_000_000_000_000_000;
+        let matrix = Matrix::from([[1u64, 1], [1, 0]]).map(F::from);
+        assert_eq!(fibonacci_nth::<F>(n), matrix.pow(n)[(0, 1)]);
+        assert_eq!(
+            F::from(fibonacci_mod(n, 998_244_353)),
+            matrix.pow(n)[(0, 1)]
+        );
+        assert_eq!(fibonacci_mod(5, 1), 0);
+    }
+
+    #[test]
+    fn test_fibonacci_digits() {
+        assert_eq!(leading_digits(0, 3), 0);
+        assert_eq!(leading_digits(12, 2), 14);
+        assert_eq!(leading_digits(12, 5), 144);
+
+        // Both sides of the switch to the approximation agree
+        for n in 185..=187 {
+            let digits = fibonacci_nth::<BigUint>(n).to_string();
+
+            for k in 1..=12 {
+                assert_eq!(leading_digits(n, k), approx_leading_digits(n, k));
+                assert_eq!(leading_digits(n, k).to_string(), digits[..k as usize]);
+            }
+        }
+
+        for n in [187, 300, 541, 2749] {
+            let digits = fibonacci_nth::<BigUint>(n).to_string();
+
+            for k in 1..=12 {
+                assert_eq!(leading_digits(n, k).to_string(), digits[..k as usize]);
+                assert_eq!(
+                    trailing_digits(n, k),
+                    digits[digits.len() - k as usize..].parse().unwrap()
+                );
+            }
+        }
+
+        // F(10^18) starts with 2628978818... and ends with ...9560546875
+        assert_eq!(leading_digits(1_000_000_000_000_000_000, 10), 2_628_978_818);
+        assert_eq!(
+            trailing_digits(1_000_000_000_000_000_000, 10),
+            9_560_546_875
+        );
+    }
+
+    fn triple(a: u64, b: u64, c: u64) -> PythagoreanTriple {
+        PythagoreanTriple { a, b, c }
+    }
+
+    #[test]
+    fn test_pythagorean_triples() {
+        let mut primitives: Vec<_> = pythagorean_triples(70).collect();
+        primitives.sort_by_key(|t| (t.perimeter(), t.a));
+
+        assert_eq!(
+            primitives,
+            [
+                triple(3, 4, 5),
+                triple(5, 12, 13),
+                triple(8, 15, 17),
+                triple(7, 24, 25),
+                triple(20, 21, 29)
+            ]
+        );
+
+        let mut all: Vec<_> = pythagorean_triples(30).with_multiples().collect();
+        all.sort();
+        assert_eq!(all, [triple(3, 4, 5), triple(5, 12, 13), triple(6, 8, 10)]);
+
+        let mut by_hypotenuse: Vec<_> = pythagorean_triples_by_hypotenuse(15)
+            .with_multiples()
+            .collect();
+
+        by_hypotenuse.sort();
+
+        assert_eq!(
+            by_hypotenuse,
+            [
+                triple(3, 4, 5),
+                triple(5, 12, 13),
+                triple(6, 8, 10),
+                triple(9, 12, 15)
+            ]
+        );
+
+        // Compare with a brute force count of triples by perimeter
+        let counts = count_triples_by(
+            pythagorean_triples(300).with_multiples(),
+            300,
+            PythagoreanTriple::perimeter,
+        );
+
+        for p in 0..=300u64 {
+            let brute = (1..p)
+                .flat_map(|a| (a + 1..p - a).map(move |b| (a, b, p - a - b)))
+                .filter(|(a, b, c)| a * a + b * b == c * c)
+                .count();
+
+            assert_eq!(counts[p as usize] as usize, brute);
+        }
+    }
+
+    #[test]
+    fn test_berlekamp_massey() {
+        let fib: Vec<F> = fibonacci::<u64>().take(10).map(F::from).collect();
+        let rec = LinearRecurrence::find(fib.iter().copied());
+        assert_eq!(rec.coefs(), [F::one(), F::one()]);
+
+        // n² satisfies a_n = 3a_{n-1} - 3a_{n-2} + a_{n-3}
+        let squares = (0..10u64).map(|n| F::from(n * n));
+        let rec = LinearRecurrence::find(squares);
+        assert_eq!(rec.coefs(), [F::from(3u64), -F::from(3u64), F::one()]);
+
+        // Geometric sequence over rationals
+        let rec = LinearRecurrence::find((0..6).map(|k| Rational64::new(1, 1 << k)));
+        assert_eq!(rec.coefs(), [Rational64::new(1, 2)]);
+
+        let rec = LinearRecurrence::find(vec![F::zero(); 5]);
+        assert_eq!(rec.order(), 0);
+        assert_eq!(rec.nth(1_000), F::zero());
+    }
+
+    #[test]
+    fn test_nth() {
+        let fib = LinearRecurrence::new(vec![F::one(), F::one()], vec![F::zero(), F::one()]);
+        assert_eq!(fib.nth(1), F::one());
+        assert_eq!(fib.nth(10), F::from(55u64));
+
+        let n = 1_000_000_000_000_000_000;
+        let matrix = Matrix::from([[1u64, 1], [1, 0]]).map(F::from);
+        assert_eq!(fib.nth(n), matrix.pow(n)[(0, 1)]);
+
+        let squares = (0..10u64).map(|n| F::from(n * n));
+        assert_eq!(
+            linear_recurrence_nth(squares, 12345),
+            F::from(12345u64 * 12345)
+        );
+
+        let tribonacci = [0, 0, 1, 1, 2, 4, 7, 13, 24, 44]
+            .iter()
+            .map(|&x: &u64| F::from(x));
+        assert_eq!(
+            linear_recurrence_nth(tribonacci, 37),
+            F::from(1_132_436_852u64)
+        );
+    }
+}
@@ -0,0 +1,293 @@
+// ---
+// --- English numbers
+// ---
+
+const UNITS: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const SCALES: [&str; 7] = [
+    "",
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+];
+
+/// Spell out a number in `1..1000`.
+fn below_thousand_words(x: u64) -> String {
+    let below_hundred = |x: u64| match x {
+        0..=19 => UNITS[x as usize].to_string(),
+        _ if x.is_multiple_of(10) => TENS[x as usize / 10].to_string(),
+        _ => format!("{}-{}", TENS[x as usize / 10], UNITS[x as usize % 10]),
+    };
+
+    match (x / 100, x % 100) {
+        (0, rest) => below_hundred(rest),
+        (hundreds, 0) => format!("{} hundred", UNITS[hundreds as usize]),
+        (hundreds, rest) => format!(
+            "{} hundred and {}",
+            UNITS[hundreds as usize],
+            below_hundred(rest)
+        ),
+    }
+}
+
+/// Spell out a number in British English, such as "three hundred and
+/// forty-two" or "one thousand and one".
+pub fn number_words(x: u64) -> String {
+    if x == 0 {
+        return UNITS[0].to_string();
+    }
+
+    // Groups of three digits, from the lowest one
+    let groups: Vec<_> = std::iter::successors(Some(x), |&x| Some(x / 1000).filter(|&x| x > 0))
+        .map(|x| x % 1000)
+        .collect();
+
+    let mut words: Vec<String> = (groups.iter().enumerate().rev())
+        .filter(|(_, &group)| group > 0)
+        .map(|(scale, &group)| match SCALES[scale] {
+            "" => below_thousand_words(group),
+            name => format!("{} {}", below_thousand_words(group), name),
+        })
+        .collect();
+
+    // A last group without hundreds is introduced by "and"
+    if groups.len() > 1 && (1..100).contains(&groups[0]) {
+        let last = words.last_mut().unwrap();
+        *last = format!("and {}", last);
+    }
+
+    words.join(" ")
+}
+
+// ---
+// --- Roman numerals
+// ---
+
+/// Symbols of the minimal form of Roman numerals, including subtractive pairs.
+const ROMAN_SYMBOLS: [(&str, u64); 13] = [
+    ("M", 1000),
+    ("CM", 900),
+    ("D", 500),
+    ("CD", 400),
+    ("C", 100),
+    ("XC", 90),
+    ("L", 50),
+    ("XL", 40),
+    ("X", 10),
+    ("IX", 9),
+    ("V", 5),
+    ("IV", 4),
+    ("I", 1),
+];
+
+fn roman_digit(c: u8) -> Option<u64> {
+    match c {
+        b'I' => Some(1),
+        b'V' => Some(5),
+        b'X' => Some(10),
+        b'L' => Some(50),
+        b'C' => Some(100),
+        b'D' => Some(500),
+        b'M' => Some(1000),
+        _ => None,
+    }
+}
+
+/// Write a positive number as a Roman numeral in minimal form, thousands
+/// being written with as many "M" as required.
+pub fn format_roman(mut x: u64) -> String {
+    let mut res = String::new();
+
+    for &(symbol, value) in &ROMAN_SYMBOLS {
+        while x >= value {
+            res.push_str(symbol);
+            x -= value;
+        }
+    }
+
+    res
+}
+
+/// Parse a Roman numeral that is not necessarily in minimal form. Symbols must
+/// be in non-increasing order, where only I, X and C can be subtracted from
+/// one of the two next symbols. Symbols following a subtractive pair must be
+/// lower than the subtracted one, which rejects numerals such as "IXV", and V,
+/// L and D can be used at most once.
+pub fn parse_roman(numeral: &str) -> Option<u64> {
+    let digits = (numeral.bytes().map(roman_digit)).collect::<Option<Vec<_>>>()?;
+
+    if [5, 50, 500]
+        .iter()
+        .any(|d| digits.iter().filter(|x| *x == d).count() > 1)
+    {
+        return None;
+    }
+    let mut total: u64 = 0;
+    // Largest value allowed for the next symbol or subtractive pair
+    let mut max_next = u64::MAX;
+    let mut i = 0;
+
+    while i < digits.len() {
+        let (value, max) = match digits.get(i + 1) {
+            Some(&next) if next > digits[i] => {
+                if ![1, 10, 100].contains(&digits[i]) || next > 10 * digits[i] {
+                    return None;
+                }
+
+                i += 2;
+                (next - digits[i - 2], digits[i - 2] - 1)
+            }
+            _ => {
+                i += 1;
+                (digits[i - 1], digits[i - 1])
+            }
+        };
+
+        if value > max_next {
+            return None;
+        }
+
+        total = total.checked_add(value)?;
+        max_next = max;
+    }
+
+    Some(total)
+}
+
+/// Rewrite a Roman numeral in its minimal form.
+pub fn minimize_roman(numeral: &str) -> Option<String> {
+    parse_roman(numeral).map(format_roman)
+}
+
+// ---
+// --- Word values
+// ---
+
+/// Sum of the positions in the alphabet of letters of a word, ignoring case
+/// and other characters.
+pub fn word_value(word: &str) -> u64 {
+    (word.bytes())
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| u64::from(b.to_ascii_uppercase() - b'A') + 1)
+        .sum()
+}
+
+/// Check if `x` is of the form n(n+1)/2.
+pub fn is_triangular(x: u64) -> bool {
+    // 8x + 1 = (2n + 1)², which may overflow a u64
+    let delta = 8 * u128::from(x) + 1;
+    let root = delta.isqrt();
+    root * root == delta
+}
+
+/// Check if the value of a word is a triangular number.
+pub fn is_triangle_word(word: &str) -> bool {
+    is_triangular(word_value(word))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_number_words() {
+        assert_eq!(number_words(0), "zero");
+        assert_eq!(number_words(16), "sixteen");
+        assert_eq!(number_words(40), "forty");
+        assert_eq!(number_words(115), "one hundred and fifteen");
+        assert_eq!(number_words(342), "three hundred and forty-two");
+        assert_eq!(number_words(1000), "one thousand");
+        assert_eq!(number_words(1001), "one thousand and one");
+        assert_eq!(number_words(1100), "one thousand one hundred");
+        assert_eq!(number_words(2_000_067), "two million and sixty-seven");
+        assert_eq!(
+            number_words(u64::MAX),
+            "eighteen quintillion four hundred and forty-six quadrillion seven hundred and \
+             forty-four trillion seventy-three billion seven hundred and nine million five \
+             hundred and fifty-one thousand six hundred and fifteen"
+        );
+    }
+
+    #[test]
+    fn test_roman() {
+        assert_eq!(format_roman(0), "");
+        assert_eq!(format_roman(14), "XIV");
+        assert_eq!(format_roman(1994), "MCMXCIV");
+        assert_eq!(format_roman(4009), "MMMMIX");
+
+        assert_eq!(parse_roman("XIV"), Some(14));
+        assert_eq!(parse_roman("MCMXCIV"), Some(1994));
+        assert_eq!(parse_roman("IIIIIIIII"), Some(9));
+        assert_eq!(parse_roman("XXXXVIIII"), Some(49));
+        assert_eq!(parse_roman(""), Some(0));
+        assert_eq!(parse_roman("IL"), None);
+        assert_eq!(parse_roman("VX"), None);
+        assert_eq!(parse_roman("IIX"), None);
+        assert_eq!(parse_roman("XIIV"), None);
+        assert_eq!(parse_roman("XA"), None);
+        assert_eq!(parse_roman("IXV"), None);
+        assert_eq!(parse_roman("XCX"), None);
+        assert_eq!(parse_roman("IVI"), None);
+        assert_eq!(parse_roman("CMD"), None);
+        assert_eq!(parse_roman("VV"), None);
+        assert_eq!(parse_roman("LXL"), None);
+        assert_eq!(parse_roman("DCD"), None);
+        assert_eq!(parse_roman("VIV"), None);
+        assert_eq!(parse_roman("XCIX"), Some(99));
+        assert_eq!(parse_roman("MDCLXVI"), Some(1666));
+        assert_eq!(parse_roman("XCV"), Some(95));
+        assert_eq!(parse_roman("XIX"), Some(19));
+
+        for x in 1..=5000 {
+            assert_eq!(parse_roman(&format_roman(x)), Some(x));
+        }
+
+        assert_eq!(minimize_roman("XIIIIII").as_deref(), Some("XVI"));
+        assert_eq!(
+            minimize_roman("MDCCCCLXXXXVIIII").as_deref(),
+            Some("MCMXCIX")
+        );
+    }
+
+    #[test]
+    fn test_word_value() {
+        assert_eq!(word_value("SKY"), 55);
+        assert_eq!(word_value("colin"), 53);
+        assert_eq!(word_value("\"A-B\""), 3);
+        assert!(is_triangle_word("SKY"));
+        assert!(!is_triangle_word("COLIN"));
+
+        let triangular: Vec<_> = (0..30).filter(|&x| is_triangular(x)).collect();
+        assert_eq!(triangular, [0, 1, 3, 6, 10, 15, 21, 28]);
+        assert!(!is_triangular(u64::MAX));
+        assert!(is_triangular(3_000_000_000 * 6_000_000_001));
+    }
+}